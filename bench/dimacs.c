@@ -0,0 +1,121 @@
+/*
+ * A reference .cnf parser in C used to compare the throughput of this crate
+ * against. It follows the parser of MiniSat (`parse_DIMACS` in `Dimacs.h`):
+ * comments are skipped line by line and literals are parsed digit by digit
+ * into a growing array of literals and clause ends.
+ *
+ * Build and run it with
+ *
+ *     cc -O2 -o dimacs bench/dimacs.c && ./dimacs bench/bf0432-007.cnf
+ *
+ * Each file is read into memory once and parsed repeatedly, so the reported
+ * throughput excludes I/O just like the `cargo bench` benchmarks.
+ */
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <time.h>
+
+#define ITERATIONS 10000
+
+typedef struct {
+    int *data;
+    size_t len, cap;
+} vec;
+
+static void push(vec *v, int x) {
+    if (v->len == v->cap) {
+        v->cap = v->cap ? 2 * v->cap : 16;
+        v->data = realloc(v->data, v->cap * sizeof(int));
+        if (!v->data) {
+            perror("realloc");
+            exit(1);
+        }
+    }
+    v->data[v->len++] = x;
+}
+
+static void skip_whitespace(const char **in) {
+    while ((**in >= 9 && **in <= 13) || **in == ' ')
+        (*in)++;
+}
+
+static void skip_line(const char **in) {
+    while (**in && *(*in)++ != '\n')
+        ;
+}
+
+static int parse_int(const char **in) {
+    int val = 0, neg = 0;
+    skip_whitespace(in);
+    if (**in == '-')
+        neg = 1, (*in)++;
+    else if (**in == '+')
+        (*in)++;
+    if (**in < '0' || **in > '9') {
+        fprintf(stderr, "unexpected char: %c\n", **in);
+        exit(3);
+    }
+    while (**in >= '0' && **in <= '9')
+        val = val * 10 + (*(*in)++ - '0');
+    return neg ? -val : val;
+}
+
+/* Parses the given null-terminated input and returns the number of clauses. */
+static size_t parse_dimacs(const char *in, vec *lits, vec *ends) {
+    lits->len = 0;
+    ends->len = 0;
+    for (;;) {
+        skip_whitespace(&in);
+        if (*in == 0)
+            return ends->len;
+        if (*in == 'p' || *in == 'c') {
+            skip_line(&in);
+            continue;
+        }
+        for (;;) {
+            int lit = parse_int(&in);
+            if (lit == 0)
+                break;
+            push(lits, lit);
+        }
+        push(ends, (int)lits->len);
+    }
+}
+
+int main(int argc, char **argv) {
+    vec lits = {0}, ends = {0};
+    for (int i = 1; i < argc; i++) {
+        FILE *file = fopen(argv[i], "rb");
+        if (!file) {
+            perror(argv[i]);
+            return 1;
+        }
+        fseek(file, 0, SEEK_END);
+        long len = ftell(file);
+        rewind(file);
+        char *input = malloc(len + 1);
+        if (fread(input, 1, len, file) != (size_t)len) {
+            perror(argv[i]);
+            return 1;
+        }
+        input[len] = 0;
+        fclose(file);
+
+        size_t num_clauses = 0;
+        struct timespec start, end;
+        clock_gettime(CLOCK_MONOTONIC, &start);
+        for (int iter = 0; iter < ITERATIONS; iter++)
+            num_clauses += parse_dimacs(input, &lits, &ends);
+        clock_gettime(CLOCK_MONOTONIC, &end);
+
+        double secs = (end.tv_sec - start.tv_sec) + (end.tv_nsec - start.tv_nsec) / 1e9;
+        printf("%-28s %10.0f ns/iter %8.0f MB/s (%zu clauses)\n", argv[i],
+               secs * 1e9 / ITERATIONS, (double)len * ITERATIONS / secs / 1e6,
+               num_clauses / ITERATIONS);
+        free(input);
+    }
+    free(lits.data);
+    free(ends.data);
+    return 0;
+}
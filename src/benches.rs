@@ -0,0 +1,54 @@
+//! Benchmarks parsing the `.cnf` files in `bench/` with the fast path of
+//! `parse_dimacs_bytes`, into a `ClauseDb` and with the buffered streaming `read_dimacs`.
+//!
+//! Run them with `cargo +nightly bench --features bench`. The reference C parser in
+//! `bench/dimacs.c` reports its throughput on the same files for comparison.
+
+use test::{black_box, Bencher};
+
+use crate::db::ClauseDb;
+use crate::parser::{parse_clause_db, parse_dimacs_bytes, read_dimacs};
+
+fn bench_fast(bencher: &mut Bencher, input: &[u8]) {
+    bencher.bytes = input.len() as u64;
+    bencher.iter(|| parse_dimacs_bytes(black_box(input)).expect("valid .cnf"));
+}
+
+fn bench_clause_db(bencher: &mut Bencher, input: &[u8]) {
+    bencher.bytes = input.len() as u64;
+    bencher.iter(|| -> ClauseDb { parse_clause_db(black_box(input)).expect("valid .cnf") });
+}
+
+fn bench_streaming(bencher: &mut Bencher, input: &[u8]) {
+    bencher.bytes = input.len() as u64;
+    bencher.iter(|| read_dimacs(black_box(input)).expect("valid .cnf"));
+}
+
+macro_rules! benches {
+    ($($fast:ident, $clause_db:ident, $streaming:ident => $file:expr;)*) => {
+        $(
+            #[bench]
+            fn $fast(bencher: &mut Bencher) {
+                bench_fast(bencher, include_bytes!(concat!("../bench/", $file)));
+            }
+
+            #[bench]
+            fn $clause_db(bencher: &mut Bencher) {
+                bench_clause_db(bencher, include_bytes!(concat!("../bench/", $file)));
+            }
+
+            #[bench]
+            fn $streaming(bencher: &mut Bencher) {
+                bench_streaming(bencher, include_bytes!(concat!("../bench/", $file)));
+            }
+        )*
+    };
+}
+
+benches! {
+    fast_aim_50, clause_db_aim_50, streaming_aim_50 => "aim-50-1_6-yes1-4.cnf";
+    fast_aim_100, clause_db_aim_100, streaming_aim_100 => "aim-100-1_6-no-1.cnf";
+    fast_bf0432, clause_db_bf0432, streaming_bf0432 => "bf0432-007.cnf";
+    fast_par_8, clause_db_par_8, streaming_par_8 => "par-8-1-c.cnf";
+    fast_zebra, clause_db_zebra, streaming_zebra => "zebra-v155-c1135.cnf";
+}
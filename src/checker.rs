@@ -0,0 +1,646 @@
+//! A DRAT proof checker verifying proofs of unsatisfiability of `.cnf` instances.
+//!
+//! The checker first applies all steps of the proof up to the first empty clause and
+//! verifies that unit propagation over the resulting clause database yields a conflict.
+//! Afterwards it checks the lemmas backwards, only verifying lemmas that contributed to a
+//! conflict, marking the clauses involved in every conflict as part of the core.
+//!
+//! A lemma is verified if it has the reverse unit propagation (RUP) property or, failing that,
+//! the resolution asymmetric tautology (RAT) property on its first literal.
+//!
+//! Unit propagation watches two literals of every clause. Unlike the checkers used for
+//! competition sized proofs, the assignment is rebuilt from the unit clauses for every
+//! lemma instead of being kept on a persistent trail, and no core-first propagation is done.
+//!
+//! Tautological clauses are satisfied by every assignment and thus ignored by
+//! propagation and RAT checks, so they never end up in the core or the LRAT proof.
+
+use std::collections::{HashMap, HashSet};
+use std::error;
+use std::fmt;
+
+use crate::items::*;
+
+/// Represents the reason why a DRAT proof could not be verified.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CheckError {
+    /// The checked instance is no `.cnf` instance or has XOR clauses.
+    NotCnf,
+
+    /// The lemma added by the proof step with the given index is neither RUP nor RAT.
+    LemmaNotImplied {
+        /// The index of the proof step within the proof.
+        step: usize,
+    },
+
+    /// Unit propagation over the clauses derived by the proof does not yield a conflict.
+    NoConflict,
+
+    /// A clause introduces variables too far above the variables seen before.
+    ///
+    /// Every clause may only use variables up to the greatest variable seen before, starting
+    /// with the declared number of variables, plus its number of literals.
+    VarOutOfRange {
+        /// The index of the proof step within the proof or `None` for a clause of the instance.
+        step: Option<usize>,
+    },
+}
+
+impl fmt::Display for CheckError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CheckError::NotCnf => write!(f, "only proofs of `.cnf` instances can be checked"),
+            CheckError::LemmaNotImplied { step } => {
+                write!(f, "the lemma of proof step {} is neither RUP nor RAT", step)
+            }
+            CheckError::NoConflict => write!(f, "the proof does not derive the empty clause"),
+            CheckError::VarOutOfRange { step: Some(step) } => {
+                write!(
+                    f,
+                    "the lemma of proof step {} uses a variable out of range",
+                    step
+                )
+            }
+            CheckError::VarOutOfRange { step: None } => {
+                write!(f, "a clause of the instance uses a variable out of range")
+            }
+        }
+    }
+}
+
+impl error::Error for CheckError {}
+
+/// Represents the result of a successfully verified DRAT proof.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckedProof {
+    core: Box<[usize]>,
+    lrat: Box<[LratStep]>,
+}
+
+impl CheckedProof {
+    /// Returns the indices of the clauses of the instance that form an unsatisfiable core.
+    pub fn core(&self) -> &[usize] {
+        &self.core
+    }
+
+    /// Returns the steps of a LRAT proof only deriving the lemmas required for the conflict
+    /// and ending with the empty clause.
+    ///
+    /// The clauses of the instance are identified by their index plus one and
+    /// derived lemmas are numbered consecutively afterwards.
+    pub fn lrat(&self) -> &[LratStep] {
+        &self.lrat
+    }
+}
+
+/// Represents a conflict found by unit propagation.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Conflict {
+    /// All literals of the clause are false.
+    Clause(usize),
+
+    /// The assumed literal is already false.
+    Lit(i64),
+}
+
+fn var_index(lit: i64) -> usize {
+    lit.unsigned_abs() as usize
+}
+
+fn lit_index(lit: i64) -> usize {
+    2 * var_index(lit) + (lit < 0) as usize
+}
+
+/// Returns the new greatest variable after the given clause if its variables are at most
+/// `max_var` plus its number of literals.
+///
+/// This bounds the memory of the dense per-variable state by the size of the input
+/// instead of the values of its literals.
+fn bound_vars(clause: &Clause, max_var: u64) -> Option<u64> {
+    let bound = max_var.saturating_add(clause.len() as u64);
+    clause
+        .lits()
+        .iter()
+        .map(|lit| lit.var().to_u64())
+        .try_fold(max_var, |max_var, var| {
+            if var <= bound {
+                Some(max_var.max(var))
+            } else {
+                None
+            }
+        })
+}
+
+#[derive(Debug, Default)]
+struct Checker {
+    clauses: Vec<Box<[i64]>>,
+    active: Vec<bool>,
+    core: Vec<bool>,
+
+    /// the clauses containing a literal indexed by `lit_index`, used for RAT checks
+    occurs: Vec<Vec<usize>>,
+
+    /// the clauses watching a literal indexed by `lit_index`
+    watches: Vec<Vec<usize>>,
+
+    /// the positions of the two watched literals of every clause with at least two literals
+    watched: Vec<[usize; 2]>,
+
+    /// the clauses consisting of a single literal
+    units: Vec<usize>,
+
+    /// the clauses without any literals
+    empties: Vec<usize>,
+
+    /// the truth values of variables, `1` for true, `-1` for false and `0` if unassigned
+    values: Vec<i8>,
+
+    /// the clauses that implied the truth values of variables
+    reasons: Vec<Option<usize>>,
+
+    /// the assigned literals in order of their assignment
+    trail: Vec<i64>,
+
+    /// the position of the next literal on the trail to propagate
+    head: usize,
+}
+
+impl Checker {
+    /// Adds a new active clause and returns its index.
+    ///
+    /// Duplicate literals are removed while keeping the order of the first occurrences.
+    fn add(&mut self, clause: &Clause) -> usize {
+        let mut unique = HashSet::with_capacity(clause.len());
+        let lits: Vec<i64> = clause
+            .lits()
+            .iter()
+            .map(|lit| lit.to_i64())
+            .filter(|&lit| unique.insert(lit))
+            .collect();
+        let tautology = lits.iter().any(|&lit| unique.contains(&-lit));
+        let index = self.clauses.len();
+        for &lit in &lits {
+            if lit_index(lit) >= self.occurs.len() {
+                self.occurs.resize(lit_index(lit) + 2, Vec::new());
+                self.watches.resize(lit_index(lit) + 2, Vec::new());
+                self.values.resize(var_index(lit) + 1, 0);
+                self.reasons.resize(var_index(lit) + 1, None);
+            }
+            if !tautology {
+                self.occurs[lit_index(lit)].push(index);
+            }
+        }
+        match lits.len() {
+            _ if tautology => (),
+            0 => self.empties.push(index),
+            1 => self.units.push(index),
+            _ => {
+                self.watches[lit_index(lits[0])].push(index);
+                self.watches[lit_index(lits[1])].push(index);
+            }
+        }
+        self.watched.push([0, 1]);
+        self.clauses.push(lits.into_boxed_slice());
+        self.active.push(true);
+        self.core.push(false);
+        index
+    }
+
+    fn value(&self, lit: i64) -> i8 {
+        let value = self.values[var_index(lit)];
+        if lit < 0 {
+            -value
+        } else {
+            value
+        }
+    }
+
+    fn assign(&mut self, lit: i64, reason: Option<usize>) {
+        self.values[var_index(lit)] = if lit < 0 { -1 } else { 1 };
+        self.reasons[var_index(lit)] = reason;
+        self.trail.push(lit);
+    }
+
+    fn assume(&mut self, lit: i64) -> Result<(), Conflict> {
+        if lit_index(lit) >= self.occurs.len() {
+            // The variable does not occur in any clause and thus never conflicts.
+            return Ok(());
+        }
+        match self.value(lit) {
+            1 => Ok(()),
+            -1 => Err(Conflict::Lit(lit)),
+            _ => {
+                self.assign(lit, None);
+                Ok(())
+            }
+        }
+    }
+
+    /// Clears the assignment and assigns the literals of all active unit clauses.
+    fn start(&mut self) -> Result<(), Conflict> {
+        for &lit in &self.trail {
+            self.values[var_index(lit)] = 0;
+            self.reasons[var_index(lit)] = None;
+        }
+        self.trail.clear();
+        self.head = 0;
+        if let Some(&empty) = self.empties.iter().find(|&&empty| self.active[empty]) {
+            return Err(Conflict::Clause(empty));
+        }
+        for i in 0..self.units.len() {
+            let unit = self.units[i];
+            if !self.active[unit] {
+                continue;
+            }
+            let lit = self.clauses[unit][0];
+            match self.value(lit) {
+                1 => (),
+                -1 => return Err(Conflict::Clause(unit)),
+                _ => self.assign(lit, Some(unit)),
+            }
+        }
+        Ok(())
+    }
+
+    /// Visits a clause watching the falsified literal and returns the literal it watches
+    /// instead, if any, or otherwise propagates the other watched literal.
+    fn visit(&mut self, clause: usize, falsified: i64) -> Result<Option<i64>, Conflict> {
+        let mut watched = self.watched[clause];
+        if self.clauses[clause][watched[0]] == falsified {
+            watched.swap(0, 1);
+        }
+        let other = self.clauses[clause][watched[0]];
+        if self.value(other) == 1 {
+            return Ok(None);
+        }
+        for pos in 0..self.clauses[clause].len() {
+            let lit = self.clauses[clause][pos];
+            if pos != watched[0] && pos != watched[1] && self.value(lit) != -1 {
+                self.watched[clause] = [watched[0], pos];
+                return Ok(Some(lit));
+            }
+        }
+        self.watched[clause] = watched;
+        if self.value(other) == -1 {
+            return Err(Conflict::Clause(clause));
+        }
+        self.assign(other, Some(clause));
+        Ok(None)
+    }
+
+    fn propagate(&mut self) -> Result<(), Conflict> {
+        while self.head < self.trail.len() {
+            let falsified = -self.trail[self.head];
+            self.head += 1;
+            let mut watching = std::mem::take(&mut self.watches[lit_index(falsified)]);
+            let mut result = Ok(());
+            let mut i = 0;
+            while i < watching.len() {
+                let clause = watching[i];
+                if !self.active[clause] {
+                    i += 1;
+                    continue;
+                }
+                match self.visit(clause, falsified) {
+                    Ok(Some(watch)) => {
+                        watching.swap_remove(i);
+                        self.watches[lit_index(watch)].push(clause);
+                    }
+                    Ok(None) => i += 1,
+                    Err(conflict) => {
+                        result = Err(conflict);
+                        break;
+                    }
+                }
+            }
+            self.watches[lit_index(falsified)] = watching;
+            result?;
+        }
+        Ok(())
+    }
+
+    /// Marks all clauses involved in the conflict as core and returns them in
+    /// the order in which they became unit, followed by the conflicting clause.
+    fn analyze(&mut self, conflict: Conflict) -> Vec<usize> {
+        let mut seen = vec![false; self.values.len()];
+        match conflict {
+            Conflict::Clause(clause) => {
+                for &lit in self.clauses[clause].iter() {
+                    seen[var_index(lit)] = true;
+                }
+            }
+            Conflict::Lit(lit) => seen[var_index(lit)] = true,
+        }
+        let mut hints = Vec::new();
+        for &lit in self.trail.iter().rev() {
+            if !seen[var_index(lit)] {
+                continue;
+            }
+            if let Some(reason) = self.reasons[var_index(lit)] {
+                hints.push(reason);
+                for &lit in self.clauses[reason].iter() {
+                    seen[var_index(lit)] = true;
+                }
+            }
+        }
+        hints.reverse();
+        if let Conflict::Clause(clause) = conflict {
+            hints.push(clause);
+        }
+        for &clause in &hints {
+            self.core[clause] = true;
+        }
+        hints
+    }
+
+    /// Checks whether the negation of the given literals yields a conflict by unit propagation
+    /// and returns the clauses involved in the conflict.
+    fn rup(&mut self, lits: &[i64]) -> Option<Vec<usize>> {
+        let result = self.start().and_then(|_| {
+            for &lit in lits {
+                self.assume(-lit)?;
+            }
+            self.propagate()
+        });
+        match result {
+            Err(conflict) => Some(self.analyze(conflict)),
+            Ok(()) => None,
+        }
+    }
+
+    /// Checks the RUP property and otherwise the RAT property on the first literal of the
+    /// given inactive clause.
+    ///
+    /// Returns the hints in LRAT notation with clauses identified by their index plus one.
+    fn check(&mut self, clause: usize) -> Option<Vec<i64>> {
+        let lits = self.clauses[clause].clone();
+        let to_hint = |clause: usize| clause as i64 + 1;
+        if let Some(hints) = self.rup(&lits) {
+            return Some(hints.into_iter().map(to_hint).collect());
+        }
+        let pivot = *lits.first()?;
+        let mut candidates = self
+            .occurs
+            .get(lit_index(-pivot))
+            .cloned()
+            .unwrap_or_default();
+        candidates.retain(|&candidate| self.active[candidate]);
+        let mut hints = Vec::new();
+        for candidate in candidates {
+            let mut resolvent = lits.to_vec();
+            resolvent.extend(self.clauses[candidate].iter().filter(|&&lit| lit != -pivot));
+            let rup = self.rup(&resolvent)?;
+            self.core[candidate] = true;
+            hints.push(-to_hint(candidate));
+            hints.extend(rup.into_iter().map(to_hint));
+        }
+        Some(hints)
+    }
+}
+
+/// Represents a proof step applied to the clause database.
+#[derive(Debug, Copy, Clone)]
+enum Applied {
+    Added(usize),
+    Deleted(usize),
+}
+
+fn key(clause: &[i64]) -> Vec<i64> {
+    let mut key = clause.to_vec();
+    key.sort_unstable();
+    key
+}
+
+/// Verifies the given DRAT proof of unsatisfiability of a `.cnf` instance.
+///
+/// Deletions of clauses that are not part of the clause database are ignored.
+/// Returns the unsatisfiable core of the instance and a trimmed LRAT proof
+/// if the proof is valid.
+pub fn check_drat(instance: &Instance, proof: &[ProofStep]) -> Result<CheckedProof, CheckError> {
+    let (mut max_var, clauses) = match *instance {
+        Instance::Cnf {
+            num_vars,
+            ref clauses,
+            ref xors,
+        } if xors.is_empty() => (num_vars, clauses),
+        _ => return Err(CheckError::NotCnf),
+    };
+    let mut checker = Checker::default();
+    let mut database: HashMap<Vec<i64>, Vec<usize>> = HashMap::new();
+    for clause in clauses.iter() {
+        max_var = bound_vars(clause, max_var).ok_or(CheckError::VarOutOfRange { step: None })?;
+        let index = checker.add(clause);
+        database
+            .entry(key(&checker.clauses[index]))
+            .or_default()
+            .push(index);
+    }
+    let mut applied = Vec::new();
+    for (step, proof_step) in proof.iter().enumerate() {
+        match *proof_step {
+            ProofStep::Add(ref clause) if clause.is_empty() => break,
+            ProofStep::Add(ref clause) => {
+                max_var = bound_vars(clause, max_var)
+                    .ok_or(CheckError::VarOutOfRange { step: Some(step) })?;
+                let index = checker.add(clause);
+                database
+                    .entry(key(&checker.clauses[index]))
+                    .or_default()
+                    .push(index);
+                applied.push((step, Applied::Added(index)));
+            }
+            ProofStep::Delete(ref clause) => {
+                let lits: Vec<i64> = clause.lits().iter().map(|lit| lit.to_i64()).collect();
+                let mut lits_key = key(&lits);
+                lits_key.dedup();
+                if let Some(index) = database
+                    .get_mut(&lits_key)
+                    .and_then(|indices| indices.pop())
+                {
+                    checker.active[index] = false;
+                    applied.push((step, Applied::Deleted(index)));
+                }
+            }
+        }
+    }
+
+    let conflict = checker.rup(&[]).ok_or(CheckError::NoConflict)?;
+    let mut hints: Vec<Option<Vec<i64>>> = vec![None; checker.clauses.len()];
+    for &(step, applied) in applied.iter().rev() {
+        match applied {
+            Applied::Deleted(index) => checker.active[index] = true,
+            Applied::Added(index) => {
+                checker.active[index] = false;
+                if checker.core[index] {
+                    hints[index] = Some(
+                        checker
+                            .check(index)
+                            .ok_or(CheckError::LemmaNotImplied { step })?,
+                    );
+                }
+            }
+        }
+    }
+
+    let mut ids: Vec<u64> = (1..=clauses.len() as u64).collect();
+    let mut next_id = clauses.len() as u64 + 1;
+    let mut lrat = Vec::new();
+    let translate = |ids: &[u64], hint: i64| {
+        let id = ids[hint.unsigned_abs() as usize - 1] as i64;
+        if hint < 0 {
+            -id
+        } else {
+            id
+        }
+    };
+    for (index, hints) in hints.into_iter().enumerate().skip(clauses.len()) {
+        ids.push(next_id);
+        if let Some(hints) = hints {
+            let lits = checker.clauses[index].iter().cloned().map(Lit::from_i64);
+            let hints = hints.iter().map(|&hint| translate(&ids, hint)).collect();
+            lrat.push(LratStep::add(
+                next_id,
+                Clause::from_vec(lits.collect()),
+                hints,
+            ));
+            next_id += 1;
+        }
+    }
+    let conflict = conflict
+        .into_iter()
+        .map(|clause| translate(&ids, clause as i64 + 1))
+        .collect();
+    lrat.push(LratStep::add(next_id, Clause::from_vec(vec![]), conflict));
+
+    let core: Vec<usize> = (0..clauses.len())
+        .filter(|&index| checker.core[index])
+        .collect();
+    Ok(CheckedProof {
+        core: core.into_boxed_slice(),
+        lrat: lrat.into_boxed_slice(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::parser::parse_dimacs;
+    use crate::proof::DratReader;
+
+    fn clause(lits: &[i64]) -> Clause {
+        Clause::from_vec(lits.iter().cloned().map(Lit::from_i64).collect())
+    }
+
+    fn read_proof(proof: &str) -> Vec<ProofStep> {
+        DratReader::text(proof.as_bytes())
+            .collect::<crate::errors::Result<_>>()
+            .expect("valid DRAT proof")
+    }
+
+    fn all_binary_clauses() -> Instance {
+        parse_dimacs("p cnf 2 4\n1 2 0\n1 -2 0\n-1 2 0\n-1 -2 0").expect("valid .cnf")
+    }
+
+    #[test]
+    fn rup_proof() {
+        let proof = read_proof("1 2 0\n1 0\n0\n");
+        let checked = check_drat(&all_binary_clauses(), &proof).expect("valid proof");
+        assert_eq!(checked.core(), &[0, 1, 2, 3]);
+        assert_eq!(
+            checked.lrat(),
+            &[
+                LratStep::add(5, clause(&[1]), vec![1, 2]),
+                LratStep::add(6, clause(&[]), vec![5, 3, 4]),
+            ]
+        );
+    }
+
+    #[test]
+    fn rat_proof() {
+        let proof = read_proof("-3 1 0\n3 0\n1 0\n0\n");
+        let checked = check_drat(&all_binary_clauses(), &proof).expect("valid proof");
+        assert_eq!(checked.core(), &[0, 1, 2, 3]);
+        assert_eq!(
+            checked.lrat(),
+            &[
+                LratStep::add(5, clause(&[-3, 1]), vec![1, 2]),
+                LratStep::add(6, clause(&[3]), vec![-5, 1, 2]),
+                LratStep::add(7, clause(&[1]), vec![6, 5]),
+                LratStep::add(8, clause(&[]), vec![7, 3, 4]),
+            ]
+        );
+    }
+
+    #[test]
+    fn tautological_lemmas() {
+        // The tautology contains the negated pivot of the RAT lemma `3` but is never
+        // needed, so it is neither checked nor part of the LRAT proof.
+        let proof = read_proof("-3 1 0\n-3 3 2 0\n3 0\n1 0\n0\n");
+        let checked = check_drat(&all_binary_clauses(), &proof).expect("valid proof");
+        assert_eq!(checked.core(), &[0, 1, 2, 3]);
+        assert_eq!(
+            checked.lrat(),
+            &[
+                LratStep::add(5, clause(&[-3, 1]), vec![1, 2]),
+                LratStep::add(6, clause(&[3]), vec![-5, 1, 2]),
+                LratStep::add(7, clause(&[1]), vec![6, 5]),
+                LratStep::add(8, clause(&[]), vec![7, 3, 4]),
+            ]
+        );
+        let instance = parse_dimacs("p cnf 1 2\n1 -1 0\n1 0").expect("valid .cnf");
+        assert_eq!(
+            check_drat(&instance, &read_proof("0\n")),
+            Err(CheckError::NoConflict)
+        );
+    }
+
+    #[test]
+    fn long_clauses() {
+        let instance = parse_dimacs("p cnf 4 5\n1 2 3 4 0\n-1 2 3 0\n-2 3 4 0\n-3 4 0\n-4 0")
+            .expect("valid .cnf");
+        let checked = check_drat(&instance, &read_proof("0\n")).expect("valid proof");
+        assert_eq!(checked.core(), &[0, 1, 2, 3, 4]);
+        assert_eq!(
+            checked.lrat(),
+            &[LratStep::add(6, clause(&[]), vec![5, 4, 3, 1, 2])]
+        );
+    }
+
+    #[test]
+    fn core_of_redundant_instance() {
+        let instance = parse_dimacs("p cnf 3 3\n1 0\n-1 0\n2 3 0").expect("valid .cnf");
+        let checked = check_drat(&instance, &read_proof("0\n")).expect("valid proof");
+        assert_eq!(checked.core(), &[0, 1]);
+        assert_eq!(checked.lrat(), &[LratStep::add(4, clause(&[]), vec![1, 2])]);
+    }
+
+    #[test]
+    fn invalid_proofs() {
+        let instance = parse_dimacs("p cnf 2 2\n1 2 0\n1 -2 0").expect("valid .cnf");
+        assert_eq!(
+            check_drat(&instance, &read_proof("-1 0\n0\n")),
+            Err(CheckError::LemmaNotImplied { step: 0 })
+        );
+        assert_eq!(
+            check_drat(&instance, &read_proof("1 0\n")),
+            Err(CheckError::NoConflict)
+        );
+        assert_eq!(
+            check_drat(&all_binary_clauses(), &read_proof("d 1 -2 0\n1 0\n0\n")),
+            Err(CheckError::LemmaNotImplied { step: 1 })
+        );
+        assert_eq!(
+            check_drat(
+                &instance,
+                &read_proof("3 0\n-1 4 5 0\n9223372036854775807 0\n0\n")
+            ),
+            Err(CheckError::VarOutOfRange { step: Some(2) })
+        );
+        let huge = parse_dimacs("p cnf 2 1\n-9223372036854775807 0").expect("valid unsafe .cnf");
+        assert_eq!(
+            check_drat(&huge, &read_proof("0\n")),
+            Err(CheckError::VarOutOfRange { step: None })
+        );
+    }
+}
@@ -0,0 +1,169 @@
+//! Transparent decompression of compressed DIMACS files.
+//!
+//! The compression format is detected by the magic bytes at the start of the input.
+//! Every format requires its cargo feature: `gzip`, `xz`, `bzip2` or `zstd`.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+
+use crate::errors::*;
+
+/// The compression formats detected by their magic bytes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Compression {
+    None,
+    Gzip,
+    Xz,
+    Bzip2,
+    Zstd,
+}
+
+impl Compression {
+    fn detect(magic: &[u8]) -> Compression {
+        if magic.starts_with(&[0x1f, 0x8b]) {
+            Compression::Gzip
+        } else if magic.starts_with(&[0xfd, b'7', b'z', b'X', b'Z', 0x00]) {
+            Compression::Xz
+        } else if magic.starts_with(b"BZh") {
+            Compression::Bzip2
+        } else if magic.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Compression::Zstd
+        } else {
+            Compression::None
+        }
+    }
+}
+
+/// Wraps the given input into a decoder for its compression format
+/// or returns it unchanged if it is not compressed.
+fn decompress<R: BufRead + 'static>(mut input: R) -> Result<Box<dyn Read>> {
+    let compression = Compression::detect(input.fill_buf().map_err(ParseError::io_at_start)?);
+    let decoder: Box<dyn Read> = match compression {
+        Compression::None => Box::new(input),
+        #[cfg(feature = "gzip")]
+        Compression::Gzip => Box::new(flate2::bufread::MultiGzDecoder::new(input)),
+        #[cfg(feature = "xz")]
+        Compression::Xz => Box::new(xz2::bufread::XzDecoder::new_multi_decoder(input)),
+        #[cfg(feature = "bzip2")]
+        Compression::Bzip2 => Box::new(bzip2::bufread::MultiBzDecoder::new(input)),
+        #[cfg(feature = "zstd")]
+        Compression::Zstd => Box::new(
+            zstd::stream::read::Decoder::with_buffer(input).map_err(ParseError::io_at_start)?,
+        ),
+        #[allow(unreachable_patterns)]
+        _ => {
+            return Err(ParseError::new(
+                Loc::new(0, 0),
+                ErrorKind::UnsupportedCompression,
+            ))
+        }
+    };
+    Ok(decoder)
+}
+
+/// Opens the file at the given path and decompresses its contents if necessary.
+pub(crate) fn open_path(path: &Path) -> Result<Box<dyn Read>> {
+    decompress(BufReader::new(
+        File::open(path).map_err(ParseError::io_at_start)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_magic_bytes() {
+        assert_eq!(Compression::detect(b"p cnf 1 1"), Compression::None);
+        assert_eq!(Compression::detect(b""), Compression::None);
+        assert_eq!(Compression::detect(&[0x1f, 0x8b, 0x08]), Compression::Gzip);
+        assert_eq!(
+            Compression::detect(&[0xfd, b'7', b'z', b'X', b'Z', 0x00, 0x00]),
+            Compression::Xz
+        );
+        assert_eq!(Compression::detect(b"BZh91AY&SY"), Compression::Bzip2);
+        assert_eq!(
+            Compression::detect(&[0x28, 0xb5, 0x2f, 0xfd]),
+            Compression::Zstd
+        );
+    }
+
+    fn read_to_string(input: &[u8]) -> Result<String> {
+        let mut decompressed = String::new();
+        decompress(std::io::Cursor::new(input.to_vec()))?
+            .read_to_string(&mut decompressed)
+            .map_err(ParseError::io_at_start)?;
+        Ok(decompressed)
+    }
+
+    #[test]
+    fn uncompressed_input() {
+        assert_eq!(
+            read_to_string(b"p cnf 1 1\n1 0\n"),
+            Ok("p cnf 1 1\n1 0\n".to_owned())
+        );
+    }
+
+    #[cfg(not(feature = "gzip"))]
+    #[test]
+    fn disabled_compression() {
+        assert_eq!(
+            read_to_string(&[0x1f, 0x8b, 0x08, 0x00]),
+            Err(ParseError::new(
+                Loc::new(0, 0),
+                ErrorKind::UnsupportedCompression
+            ))
+        );
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn gzip_input() {
+        use std::io::Write;
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(b"p cnf 1 1\n1 0\n").unwrap();
+        let compressed = encoder.finish().unwrap();
+        assert_eq!(
+            read_to_string(&compressed),
+            Ok("p cnf 1 1\n1 0\n".to_owned())
+        );
+    }
+
+    #[cfg(feature = "xz")]
+    #[test]
+    fn xz_input() {
+        let mut compressed = Vec::new();
+        xz2::read::XzEncoder::new(&b"p cnf 1 1\n1 0\n"[..], 6)
+            .read_to_end(&mut compressed)
+            .unwrap();
+        assert_eq!(
+            read_to_string(&compressed),
+            Ok("p cnf 1 1\n1 0\n".to_owned())
+        );
+    }
+
+    #[cfg(feature = "bzip2")]
+    #[test]
+    fn bzip2_input() {
+        let mut compressed = Vec::new();
+        bzip2::read::BzEncoder::new(&b"p cnf 1 1\n1 0\n"[..], bzip2::Compression::default())
+            .read_to_end(&mut compressed)
+            .unwrap();
+        assert_eq!(
+            read_to_string(&compressed),
+            Ok("p cnf 1 1\n1 0\n".to_owned())
+        );
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn zstd_input() {
+        let compressed = zstd::stream::encode_all(&b"p cnf 1 1\n1 0\n"[..], 0).unwrap();
+        assert_eq!(
+            read_to_string(&compressed),
+            Ok("p cnf 1 1\n1 0\n".to_owned())
+        );
+    }
+}
@@ -0,0 +1,246 @@
+//! An arena-backed clause database for large `.cnf` instances.
+//!
+//! Instead of allocating every clause separately, a `ClauseDb` stores the literals of all
+//! clauses in one contiguous buffer and remembers where each clause ends. The literal type
+//! is generic so that more compact literal representations can be stored as well.
+
+use std::convert::TryFrom;
+use std::ops::Index;
+use std::slice;
+
+use crate::items::*;
+use crate::scanner::ClauseSink;
+
+/// Represents the clauses of a `.cnf` instance stored in a contiguous literal buffer.
+///
+/// Literals of type `L` are converted from the parsed `Lit` by `TryFrom<Lit>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClauseDb<L = Lit> {
+    num_vars: u64,
+    lits: Vec<L>,
+
+    /// the offset after the last literal of every clause within `lits`
+    ends: Vec<usize>,
+}
+
+impl<L> ClauseDb<L> {
+    /// Creates a new empty clause database with the given number of variables.
+    pub fn new(num_vars: u64) -> ClauseDb<L> {
+        ClauseDb {
+            num_vars,
+            lits: Vec::new(),
+            ends: Vec::new(),
+        }
+    }
+
+    /// Creates a new empty clause database with the given number of variables and
+    /// capacity for the given numbers of clauses and literals.
+    pub fn with_capacity(num_vars: u64, num_clauses: usize, num_lits: usize) -> ClauseDb<L> {
+        ClauseDb {
+            num_vars,
+            lits: Vec::with_capacity(num_lits),
+            ends: Vec::with_capacity(num_clauses),
+        }
+    }
+
+    /// Returns the number of variables declared for this clause database.
+    pub fn num_vars(&self) -> u64 {
+        self.num_vars
+    }
+
+    /// Returns the number of clauses.
+    pub fn len(&self) -> usize {
+        self.ends.len()
+    }
+
+    /// Returns `true` if there are no clauses.
+    pub fn is_empty(&self) -> bool {
+        self.ends.is_empty()
+    }
+
+    /// Returns the number of literals of all clauses.
+    pub fn num_lits(&self) -> usize {
+        self.lits.len()
+    }
+
+    /// Returns the contiguous buffer of the literals of all clauses.
+    pub fn lits(&self) -> &[L] {
+        &self.lits
+    }
+
+    /// Returns the clause at the given index if any.
+    pub fn get(&self, index: usize) -> Option<ClauseRef<'_, L>> {
+        let end = *self.ends.get(index)?;
+        let start = if index == 0 { 0 } else { self.ends[index - 1] };
+        Some(ClauseRef {
+            lits: &self.lits[start..end],
+        })
+    }
+
+    /// Returns an iterator over all clauses.
+    pub fn iter(&self) -> ClauseDbIter<'_, L> {
+        ClauseDbIter {
+            lits: &self.lits,
+            start: 0,
+            ends: self.ends.iter(),
+        }
+    }
+
+    /// Appends a new clause consisting of the given literals.
+    pub fn push(&mut self, lits: &[L])
+    where
+        L: Copy,
+    {
+        self.lits.extend_from_slice(lits);
+        self.ends.push(self.lits.len());
+    }
+
+    /// Converts this clause database into a `.cnf` instance.
+    pub fn to_instance(&self) -> Instance
+    where
+        L: Copy + Into<Lit>,
+    {
+        Instance::cnf(
+            self.num_vars,
+            self.iter().map(|clause| clause.to_clause()).collect(),
+        )
+    }
+}
+
+impl<L> ClauseSink for ClauseDb<L>
+where
+    L: TryFrom<Lit>,
+{
+    fn push_lit(&mut self, lit: Lit) -> Option<()> {
+        self.lits.push(L::try_from(lit).ok()?);
+        Some(())
+    }
+
+    fn end_clause(&mut self) {
+        self.ends.push(self.lits.len());
+    }
+}
+
+impl<L> Index<usize> for ClauseDb<L> {
+    type Output = [L];
+
+    fn index(&self, index: usize) -> &[L] {
+        self.get(index).expect("clause index out of bounds").lits
+    }
+}
+
+impl<'a, L> IntoIterator for &'a ClauseDb<L> {
+    type Item = ClauseRef<'a, L>;
+    type IntoIter = ClauseDbIter<'a, L>;
+
+    fn into_iter(self) -> ClauseDbIter<'a, L> {
+        self.iter()
+    }
+}
+
+/// Represents a clause borrowed from a `ClauseDb`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ClauseRef<'a, L = Lit> {
+    lits: &'a [L],
+}
+
+impl<'a, L> ClauseRef<'a, L> {
+    /// Returns the number of literals of this clause.
+    pub fn len(&self) -> usize {
+        self.lits.len()
+    }
+
+    /// Returns `true` if this clause has no literals.
+    pub fn is_empty(&self) -> bool {
+        self.lits.is_empty()
+    }
+
+    /// Returns a slice over the literals of this clause.
+    pub fn lits(&self) -> &'a [L] {
+        self.lits
+    }
+
+    /// Copies this clause into a separately allocated `Clause`.
+    pub fn to_clause(&self) -> Clause
+    where
+        L: Copy + Into<Lit>,
+    {
+        Clause::from_vec(self.lits.iter().map(|&lit| lit.into()).collect())
+    }
+}
+
+/// An iterator over the clauses of a `ClauseDb`.
+#[derive(Debug, Clone)]
+pub struct ClauseDbIter<'a, L = Lit> {
+    lits: &'a [L],
+    start: usize,
+    ends: slice::Iter<'a, usize>,
+}
+
+impl<'a, L> Iterator for ClauseDbIter<'a, L> {
+    type Item = ClauseRef<'a, L>;
+
+    fn next(&mut self) -> Option<ClauseRef<'a, L>> {
+        let end = *self.ends.next()?;
+        let lits = &self.lits[self.start..end];
+        self.start = end;
+        Some(ClauseRef { lits })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.ends.size_hint()
+    }
+}
+
+impl<'a, L> ExactSizeIterator for ClauseDbIter<'a, L> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::parser::{parse_clause_db, parse_dimacs};
+
+    fn lits(lits: &[i64]) -> Vec<Lit> {
+        lits.iter().cloned().map(Lit::from_i64).collect()
+    }
+
+    #[test]
+    fn push_and_iter() {
+        let mut db = ClauseDb::new(3);
+        db.push(&lits(&[1, -2]));
+        db.push(&[]);
+        db.push(&lits(&[3]));
+        assert_eq!(db.len(), 3);
+        assert_eq!(db.num_lits(), 3);
+        assert_eq!(&db[0], &lits(&[1, -2])[..]);
+        assert!(db.get(1).expect("empty clause").is_empty());
+        assert_eq!(db.get(3), None);
+        let clauses: Vec<&[Lit]> = db.iter().map(|clause| clause.lits()).collect();
+        assert_eq!(
+            clauses,
+            vec![&lits(&[1, -2])[..], &lits(&[])[..], &lits(&[3])[..]]
+        );
+    }
+
+    #[test]
+    fn parse_into_db() {
+        let sample = "c comment\np cnf 3 3\n1 -2 0\n2 3 0 -1\n-3";
+        let db: ClauseDb = parse_clause_db(sample.as_bytes()).expect("valid .cnf");
+        assert_eq!(db.num_vars(), 3);
+        assert_eq!(db.lits(), &lits(&[1, -2, 2, 3, -1, -3])[..]);
+        assert_eq!(Ok(db.to_instance()), parse_dimacs(sample));
+    }
+
+    #[test]
+    fn parse_unusual_into_db() {
+        let sample = "p cnf 2 2\n- 1 2 0\n-2";
+        let db: ClauseDb = parse_clause_db(sample.as_bytes()).expect("valid .cnf");
+        assert_eq!(Ok(db.to_instance()), parse_dimacs(sample));
+        let sample = "p cnf 2 2\n1 2 0\n-2 +";
+        assert_eq!(
+            parse_clause_db::<Lit>(sample.as_bytes()).map(|db| db.to_instance()),
+            parse_dimacs(sample)
+        );
+        assert!(parse_clause_db::<Lit>(b"p sat 2\n(1)").is_err());
+    }
+}
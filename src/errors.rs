@@ -1,6 +1,14 @@
 //! Defines some error kinds and facilities to communicate errors while parsing
 //! `.cnf` or `.sat` files.
 
+use std::error;
+use std::fmt;
+use std::io;
+use std::sync::Arc;
+
+use crate::items::Lit;
+use crate::lexer::{Ident, TokenKind};
+
 /// Represents a source line and column of an error.
 /// Used to provide the user of this parser facility with necesary information
 /// to debug their input files formats.
@@ -13,10 +21,17 @@ pub struct Loc {
 impl Loc {
     /// Creates a new location with a given line and column.
     pub fn new(line: u64, col: u64) -> Loc {
-        Loc {
-            line: line,
-            col: col,
-        }
+        Loc { line, col }
+    }
+
+    /// Returns the line of this location, starting at 1.
+    pub fn line(self) -> u64 {
+        self.line
+    }
+
+    /// Returns the column of this location, starting at 1.
+    pub fn col(self) -> u64 {
+        self.col
     }
 
     /// Bumps the line of this location, resetting its column.
@@ -31,8 +46,161 @@ impl Loc {
     }
 }
 
-/// Different kinds of errors that may occure while parsing.
+impl fmt::Display for Loc {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.col)
+    }
+}
+
+/// Represents a range of byte offsets within the input, e.g. of a token or an error.
+/// The start offset is inclusive while the end offset is exclusive.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Span {
+    start: u64,
+    end: u64,
+}
+
+impl Span {
+    /// Creates a new span with the given start and end byte offsets.
+    pub fn new(start: u64, end: u64) -> Span {
+        Span { start, end }
+    }
+
+    /// Returns the inclusive start byte offset of this span.
+    pub fn start(self) -> u64 {
+        self.start
+    }
+
+    /// Returns the exclusive end byte offset of this span.
+    pub fn end(self) -> u64 {
+        self.end
+    }
+}
+
+/// Defines the `Expected` flags together with the token kinds they stand for and
+/// their human readable names, which are displayed in the order of definition.
+macro_rules! expected {
+    ($(#[doc = $doc:literal] $flag:ident = $bits:expr, $kind:pat => $name:literal;)*) => {
+        bitflags! {
+            /// A set of token kinds the parser expected at the location of an error.
+            pub struct Expected: u64 {
+                $(
+                    #[doc = $doc]
+                    const $flag = $bits;
+                )*
+            }
+        }
+
+        /// The human readable names of the token kinds within an `Expected` set in order of display.
+        const EXPECTED_NAMES: &[(Expected, &str)] = &[$((Expected::$flag, $name)),*];
+
+        impl From<TokenKind> for Expected {
+            fn from(kind: TokenKind) -> Expected {
+                match kind {
+                    TokenKind::Comment => Expected::empty(),
+                    $($kind => Expected::$flag,)*
+                }
+            }
+        }
+    };
+}
+
+expected! {
+    /// The `p` keyword.
+    PROBLEM = 1 << 9, TokenKind::Ident(Ident::Problem) => "`p`";
+    /// The `cnf` keyword.
+    CNF = 1 << 11, TokenKind::Ident(Ident::Cnf) => "`cnf`";
+    /// The `sat` keyword.
+    SAT = 1 << 12, TokenKind::Ident(Ident::Sat) => "`sat`";
+    /// The `sate` keyword.
+    SATE = 1 << 14, TokenKind::Ident(Ident::Sate) => "`sate`";
+    /// The `satx` keyword.
+    SATX = 1 << 13, TokenKind::Ident(Ident::Satx) => "`satx`";
+    /// The `satex` keyword.
+    SATEX = 1 << 15, TokenKind::Ident(Ident::Satex) => "`satex`";
+    /// The `wcnf` keyword.
+    WCNF = 1 << 16, TokenKind::Ident(Ident::Wcnf) => "`wcnf`";
+    /// The `inccnf` keyword.
+    INCCNF = 1 << 33, TokenKind::Ident(Ident::Inccnf) => "`inccnf`";
+    /// The `h` keyword.
+    HARD = 1 << 17, TokenKind::Ident(Ident::Hard) => "`h`";
+    /// The `e` keyword.
+    E = 1 << 18, TokenKind::Ident(Ident::E) => "`e`";
+    /// The `a` keyword.
+    A = 1 << 19, TokenKind::Ident(Ident::A) => "`a`";
+    /// The `n` keyword.
+    NODE = 1 << 20, TokenKind::Ident(Ident::Node) => "`n`";
+    /// The `edge` keyword.
+    EDGE = 1 << 21, TokenKind::Ident(Ident::Edge) => "`edge`";
+    /// The `col` keyword.
+    COL = 1 << 22, TokenKind::Ident(Ident::Col) => "`col`";
+    /// The `sp` keyword.
+    SP = 1 << 23, TokenKind::Ident(Ident::Sp) => "`sp`";
+    /// The `max` keyword.
+    MAX = 1 << 24, TokenKind::Ident(Ident::Max) => "`max`";
+    /// The `min` keyword.
+    MIN = 1 << 25, TokenKind::Ident(Ident::Min) => "`min`";
+    /// The `s` keyword.
+    S = 1 << 26, TokenKind::Ident(Ident::S) => "`s`";
+    /// The `t` keyword.
+    SINK = 1 << 27, TokenKind::Ident(Ident::Sink) => "`t`";
+    /// The `v` keyword.
+    VALUES = 1 << 28, TokenKind::Ident(Ident::Values) => "`v`";
+    /// The `SATISFIABLE` keyword.
+    SATISFIABLE = 1 << 29, TokenKind::Ident(Ident::Satisfiable) => "`SATISFIABLE`";
+    /// The `UNSATISFIABLE` keyword.
+    UNSATISFIABLE = 1 << 30, TokenKind::Ident(Ident::Unsatisfiable) => "`UNSATISFIABLE`";
+    /// The `UNKNOWN` keyword.
+    UNKNOWN = 1 << 31, TokenKind::Ident(Ident::Unknown) => "`UNKNOWN`";
+    /// The `d` keyword.
+    DELETE = 1 << 32, TokenKind::Ident(Ident::Delete) => "`d`";
+    /// The `x` keyword.
+    X = 1 << 34, TokenKind::Ident(Ident::X) => "`x`";
+    /// The `xor` keyword.
+    XOR = 1 << 10, TokenKind::Ident(Ident::Xor) => "`xor`";
+    /// A positive, non-zero integer value, e.g. `42`.
+    NAT = 1 << 0, TokenKind::Nat(_) => "a natural number";
+    /// A zero integer value.
+    ZERO = 1 << 1, TokenKind::Zero => "`0`";
+    /// A `+` symbol.
+    PLUS = 1 << 2, TokenKind::Plus => "`+`";
+    /// A `-` symbol.
+    MINUS = 1 << 3, TokenKind::Minus => "`-`";
+    /// A `*` symbol.
+    STAR = 1 << 4, TokenKind::Star => "`*`";
+    /// A `=` symbol.
+    EQ = 1 << 5, TokenKind::Eq => "`=`";
+    /// An opening parentheses `(`.
+    OPEN = 1 << 6, TokenKind::Open => "`(`";
+    /// A closing parentheses `)`.
+    CLOSE = 1 << 7, TokenKind::Close => "`)`";
+    /// The end of the input.
+    END_OF_FILE = 1 << 8, TokenKind::EndOfFile => "end of file";
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let names: Vec<&str> = EXPECTED_NAMES
+            .iter()
+            .filter(|&&(expected, _)| self.contains(expected))
+            .map(|&(_, name)| name)
+            .collect();
+        match names.split_last() {
+            None => Ok(()),
+            Some((last, [])) => f.write_str(last),
+            Some((last, init)) => write!(f, "{} or {}", init.join(", "), last),
+        }
+    }
+}
+
+/// Different kinds of errors that may occure while parsing.
+///
+/// Further error kinds may be added when new checks are supported.
+///
+/// Error kinds are `Clone` but not `Copy` since `ErrorKind::Io` carries the
+/// failure of the byte source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum ErrorKind {
     /// When parsing an invalid character at the start of a token.
     InvalidTokenStart,
@@ -62,33 +230,438 @@ pub enum ErrorKind {
     ExpectedNat,
 
     /// When a literal was expected but not found.
-    ExpectedLit, // IllegalXorExtensionUsed, // enhanced check
-                 // IllegalEqExtensionUsed, // enhanced check
+    ExpectedLit,
+
+    /// When a natural number does not fit into 64 bits.
+    NatOverflow,
+
+    /// When the variable of a literal is too large to be represented by a `Lit`.
+    LitOverflow,
+
+    /// When a `xor(..)` formula is used without the XOR-Extension being declared.
+    /// Only reported by the enhanced checks of `parse_safe_dimacs`.
+    IllegalXorExtensionUsed,
+
+    /// When a `=(..)` formula is used without the EQ-Extension being declared.
+    /// Only reported by the enhanced checks of `parse_safe_dimacs`.
+    IllegalEqExtensionUsed,
+
+    /// When a literal refers to a variable beyond the number of variables declared in the problem line.
+    /// Only reported by the enhanced checks of `parse_safe_dimacs`.
+    TooManyVariables,
+
+    /// When there are more clauses than declared in the problem line.
+    /// Only reported by the enhanced checks of `parse_safe_dimacs`.
+    TooManyClauses,
+
+    /// When there are fewer clauses than declared in the problem line.
+    /// Only reported by the enhanced checks of `parse_safe_dimacs`.
+    TooFewClauses,
 
-                 // TooManyVariables, // enhanced check
-                 // TooManyClauses, // enhanced check
-                 // SelfContradictingClause, // enhanced check
+    /// When a variable is quantified more than once within the prefix of a `.qdimacs` file.
+    DuplicateQuantifiedVariable,
+
+    /// When a variable is assigned more than once within the model of a solver output.
+    DuplicateModelVariable,
+
+    /// When a vertex of a graph file or a node of a network file is beyond the number of
+    /// vertices or nodes declared in the problem line.
+    InvalidVertex,
+
+    /// When a signed integer does not fit into 64 bits.
+    IntOverflow,
+
+    /// When a maximum flow problem does not designate exactly one source and one sink node.
+    InvalidFlowTerminals,
+
+    /// When a literal is not representable by the literal type it is parsed into.
+    LitOutOfRange,
+
+    /// When a compressed file is read whose compression format is not enabled
+    /// by the corresponding cargo feature.
+    UnsupportedCompression,
+
+    /// When reading from the underlying byte source failed.
+    Io(IoError),
+}
+
+impl ErrorKind {
+    /// Returns a short description of this error kind.
+    fn description(&self) -> &'static str {
+        use self::ErrorKind::*;
+        match self {
+            InvalidTokenStart => "invalid start of a token",
+            UnknownKeyword => "unknown keyword",
+            UnexpectedChar => "unexpected character",
+            UnexpectedToken => "unexpected token",
+            UnexpectedEndOfFile => "unexpected end of file",
+            EmptyTokenStream => "empty input",
+            InvalidSatExtension => "invalid SAT extension",
+            NotParsedToEnd => "input not parsed to its end",
+            ExpectedNat => "expected a natural number",
+            ExpectedLit => "expected a literal",
+            NatOverflow => "natural number overflow",
+            LitOverflow => "literal overflow",
+            IllegalXorExtensionUsed => "illegal use of the XOR-Extension",
+            IllegalEqExtensionUsed => "illegal use of the EQ-Extension",
+            TooManyVariables => "too many variables",
+            TooManyClauses => "too many clauses",
+            TooFewClauses => "too few clauses",
+            DuplicateQuantifiedVariable => "duplicate quantified variable",
+            DuplicateModelVariable => "duplicate model variable",
+            InvalidVertex => "invalid vertex",
+            IntOverflow => "integer overflow",
+            InvalidFlowTerminals => "invalid source or sink",
+            LitOutOfRange => "literal out of range",
+            UnsupportedCompression => "unsupported compression format",
+            Io(_) => "failed to read input",
+        }
+    }
+
+    /// Returns an explanation of how this error kind usually comes about.
+    fn explanation(&self) -> &'static str {
+        use self::ErrorKind::*;
+        match self {
+            InvalidTokenStart => {
+                "tokens start with a letter, a digit or one of `-`, `+`, `*`, `=`, `(` and `)`"
+            }
+            UnknownKeyword => {
+                "this word is no keyword of the supported DIMACS formats, \
+                 note that comment lines start with `c`"
+            }
+            UnexpectedChar => "this character is not allowed at this position",
+            UnexpectedToken => "this token is not allowed at this position",
+            UnexpectedEndOfFile => "the input ended before the instance was complete",
+            EmptyTokenStream => "the input does not contain any tokens",
+            InvalidSatExtension => {
+                "the problem kind of a `.sat` file is one of `sat`, `sate`, `satx` and `satex`"
+            }
+            NotParsedToEnd => "the instance is complete but the input continues",
+            ExpectedNat => "a positive number is required at this position",
+            ExpectedLit => "a literal like `42` or `-42` is required at this position",
+            NatOverflow => "natural numbers must not be greater than 18446744073709551615",
+            LitOverflow => "variables of literals must not be greater than 9223372036854775807",
+            IllegalXorExtensionUsed => {
+                "`xor(..)` formulas require the problem kind `satx` or `satex`"
+            }
+            IllegalEqExtensionUsed => "`=(..)` formulas require the problem kind `sate` or `satex`",
+            TooManyVariables => {
+                "this refers to a variable beyond the number of variables declared in the problem line"
+            }
+            TooManyClauses => "the input contains more clauses than declared in the problem line",
+            TooFewClauses => "the input contains fewer clauses than declared in the problem line",
+            DuplicateQuantifiedVariable => {
+                "every variable must be quantified at most once within the quantifier prefix"
+            }
+            DuplicateModelVariable => "a model must assign every variable at most once",
+            InvalidVertex => {
+                "vertices are numbered from 1 up to the number declared in the problem line"
+            }
+            IntOverflow => "integers must be within -9223372036854775807 and 9223372036854775807",
+            InvalidFlowTerminals => {
+                "maximum flow problems have exactly one source `n id s` and one sink `n id t` line"
+            }
+            LitOutOfRange => "the variable of this literal is too large for the chosen literal type",
+            UnsupportedCompression => {
+                "reading compressed files requires the cargo feature `gzip`, `xz`, `bzip2` or `zstd`"
+            }
+            Io(_) => "the byte source failed before its end was reached",
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ErrorKind::Io(ref err) => write!(f, "{}: {}", self.description(), err),
+            _ => f.write_str(self.description()),
+        }
+    }
+}
+
+/// The failure of a byte source as reported by `ErrorKind::Io`.
+///
+/// It is shared between clones of the error it is part of. Two `IoError`s are equal
+/// if their underlying errors are of the same `io::ErrorKind`.
+#[derive(Debug, Clone)]
+pub struct IoError(Arc<io::Error>);
+
+impl IoError {
+    /// Returns the kind of the underlying I/O error.
+    pub fn kind(&self) -> io::ErrorKind {
+        self.0.kind()
+    }
+
+    /// Returns the underlying I/O error.
+    pub fn get_ref(&self) -> &io::Error {
+        &self.0
+    }
+}
+
+impl From<io::Error> for IoError {
+    fn from(err: io::Error) -> IoError {
+        IoError(Arc::new(err))
+    }
+}
+
+impl PartialEq for IoError {
+    fn eq(&self, other: &IoError) -> bool {
+        self.kind() == other.kind()
+    }
+}
+
+impl Eq for IoError {}
+
+impl fmt::Display for IoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
 }
 
 /// Represents an error that occured while parsing.
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+///
+/// Just like its `ErrorKind`, a parse error is `Clone` but not `Copy`.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ParseError {
     /// The source location (line + column) of the error.
     pub loc: Loc,
 
     /// The kind of the error that occured.
     pub kind: ErrorKind,
+
+    /// The byte offsets within the input covered by the error.
+    pub span: Span,
+
+    /// The token that was found at the location of the error, if any.
+    pub found: Option<TokenKind>,
+
+    /// The token kinds that were expected at the location of the error.
+    pub expected: Expected,
 }
 
 impl ParseError {
+    /// Creates an `ErrorKind::Io` error of a byte source that failed before its first byte
+    /// was read at the location the `Lexer` reports such failures.
+    pub(crate) fn io_at_start(err: io::Error) -> ParseError {
+        ParseError::new(Loc::new(1, 1), ErrorKind::Io(err.into()))
+    }
+
     /// Creates a new parser error at the given source location with the given error kind.
+    ///
+    /// The error starts out with an empty span at the start of the input, no found token
+    /// and no expected token kinds.
     pub fn new(loc: Loc, kind: ErrorKind) -> Self {
         ParseError {
-            loc: loc,
-            kind: kind,
+            loc,
+            kind,
+            span: Span::new(0, 0),
+            found: None,
+            expected: Expected::empty(),
+        }
+    }
+
+    /// Returns this error covering the given span of the input.
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = span;
+        self
+    }
+
+    /// Returns this error with the given token found at its location.
+    pub fn with_found(mut self, found: TokenKind) -> Self {
+        self.found = Some(found);
+        self
+    }
+
+    /// Returns this error with the given token kinds expected at its location.
+    pub fn with_expected(mut self, expected: Expected) -> Self {
+        self.expected = expected;
+        self
+    }
+}
+
+impl ParseError {
+    /// Renders this error as human readable diagnostic for the given input that caused it.
+    ///
+    /// The rendered diagnostic contains the offending line of the input with carets
+    /// underlining the span of the error followed by an explanation of the error.
+    pub fn render(&self, input: &str) -> String {
+        let mut out = format!("error: {}\n", self);
+        let line = self.loc.line as usize;
+        if line != 0 {
+            let source = input
+                .split('\n')
+                .nth(line - 1)
+                .unwrap_or("")
+                .trim_end_matches('\r');
+            let start = (self.loc.col as usize).saturating_sub(1).min(source.len());
+            // Keep tabs of the source line so that the carets line up with it.
+            let indent: String = source.as_bytes()[..start]
+                .iter()
+                .filter(|&&b| b & 0xC0 != 0x80)
+                .map(|&b| if b == b'\t' { '\t' } else { ' ' })
+                .collect();
+            let len = (self.span.end.saturating_sub(self.span.start) as usize)
+                .min(source.len() - start)
+                .max(1);
+            let gutter = " ".repeat(line.to_string().len());
+            out.push_str(&format!("{} |\n", gutter));
+            out.push_str(&format!("{} | {}\n", line, source));
+            out.push_str(&format!("{} | {}{}\n", gutter, indent, "^".repeat(len)));
+        }
+        out.push_str(&format!("= note: {}\n", self.kind.explanation()));
+        out
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at {}", self.kind, self.loc)?;
+        if !self.expected.is_empty() {
+            write!(f, ": expected {}", self.expected)?;
+        }
+        if let Some(found) = self.found {
+            let sep = if self.expected.is_empty() { ':' } else { ',' };
+            write!(f, "{} found {}", sep, found)?;
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self.kind {
+            ErrorKind::Io(ref err) => Some(err.get_ref()),
+            _ => None,
         }
     }
 }
 
+/// Represents a violated invariant of a hand-built item.
+///
+/// Further violations may be added when new items are supported.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ValidationError {
+    /// A literal is `0` which is reserved as the terminator of clauses.
+    ZeroLit,
+
+    /// A literal is `i64::MIN` whose variable does not fit into 63 bits.
+    LitOverflow,
+
+    /// A literal refers to a variable beyond the number of variables of its instance.
+    VarOutOfRange {
+        /// The offending literal.
+        lit: Lit,
+
+        /// The number of variables of the instance.
+        num_vars: u64,
+    },
+
+    /// An n-ary formula like `*()` has no inner formulas.
+    EmptyFormula,
+
+    /// A soft clause has the weight `0` which is not representable in `.wcnf` files.
+    ZeroWeight,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ValidationError::ZeroLit => f.write_str("literals must not be zero"),
+            ValidationError::LitOverflow => {
+                f.write_str("variables of literals must not be greater than 9223372036854775807")
+            }
+            ValidationError::VarOutOfRange { lit, num_vars } => write!(
+                f,
+                "literal {} refers to a variable beyond the {} declared variables",
+                lit.to_i64(),
+                num_vars
+            ),
+            ValidationError::EmptyFormula => f.write_str("n-ary formulas must not be empty"),
+            ValidationError::ZeroWeight => f.write_str("soft clauses must not have weight zero"),
+        }
+    }
+}
+
+impl error::Error for ValidationError {}
+
 /// The result type used within this crate while parsing.
 pub type Result<T> = ::std::result::Result<T, ParseError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let err = ParseError::new(Loc::new(4, 6), ErrorKind::UnexpectedToken);
+        assert_eq!(err.to_string(), "unexpected token at line 4, column 6");
+        let io_err = io::Error::new(io::ErrorKind::BrokenPipe, "connection lost");
+        let err = ParseError::new(Loc::new(1, 3), ErrorKind::Io(io_err.into()));
+        assert_eq!(
+            err.to_string(),
+            "failed to read input: connection lost at line 1, column 3"
+        );
+        let source = error::Error::source(&err).expect("the I/O error");
+        assert_eq!(source.to_string(), "connection lost");
+    }
+
+    #[test]
+    fn display_expected_found() {
+        let err = ParseError::new(Loc::new(4, 6), ErrorKind::UnexpectedToken)
+            .with_span(Span::new(31, 32))
+            .with_found(TokenKind::Open)
+            .with_expected(Expected::NAT | Expected::MINUS | Expected::ZERO);
+        assert_eq!(
+            err.to_string(),
+            "unexpected token at line 4, column 6: \
+             expected a natural number, `0` or `-`, found `(`"
+        );
+        let err = ParseError::new(Loc::new(1, 1), ErrorKind::UnexpectedToken)
+            .with_found(TokenKind::Ident(Ident::Sat))
+            .with_expected(Expected::PROBLEM);
+        assert_eq!(
+            err.to_string(),
+            "unexpected token at line 1, column 1: expected `p`, found `sat`"
+        );
+        let err = ParseError::new(Loc::new(2, 1), ErrorKind::TooManyClauses)
+            .with_found(TokenKind::Nat(42));
+        assert_eq!(
+            err.to_string(),
+            "too many clauses at line 2, column 1: found `42`"
+        );
+    }
+
+    #[test]
+    fn render() {
+        let input = "p cnf 4 3\n\t1 3 -4 0\n\t4 ( 0\n";
+        let err = ParseError::new(Loc::new(3, 4), ErrorKind::UnexpectedToken);
+        assert_eq!(
+            err.render(input),
+            "error: unexpected token at line 3, column 4\n  |\n3 | \t4 ( 0\n  | \t  ^\n\
+             = note: this token is not allowed at this position\n"
+        );
+    }
+
+    #[test]
+    fn render_span() {
+        let input = "p cnf 4 3\n1 3 -42 0\n";
+        let err = ParseError::new(Loc::new(2, 5), ErrorKind::TooManyVariables)
+            .with_span(Span::new(14, 17));
+        assert_eq!(
+            err.render(input),
+            "error: too many variables at line 2, column 5\n  |\n2 | 1 3 -42 0\n  |     ^^^\n\
+             = note: this refers to a variable beyond the number of variables declared in the problem line\n"
+        );
+    }
+
+    #[test]
+    fn render_without_line() {
+        let err = ParseError::new(Loc::new(0, 0), ErrorKind::EmptyTokenStream);
+        assert_eq!(
+            err.render(""),
+            "error: empty input at line 0, column 0\n\
+             = note: the input does not contain any tokens\n"
+        );
+    }
+}
@@ -0,0 +1,310 @@
+//! The evaluation of clauses, formulas and instances under an `Assignment`.
+//!
+//! Evaluation is three-valued: `Some(true)` and `Some(false)` denote definite truth values
+//! whereas `None` denotes that the result depends on unassigned variables.
+
+use crate::items::*;
+
+/// Represents the part of an instance that is not satisfied by an assignment.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Falsified<'a> {
+    /// The clause at the given index within the clauses of the instance.
+    ///
+    /// For `.wcnf` instances only hard clauses are reported.
+    Clause(usize, &'a Clause),
+
+    /// The XOR clause at the given index within the XOR clauses of a `.cnf` instance.
+    Xor(usize, &'a XorClause),
+
+    /// The outermost subformula of a `.sat` instance that is not satisfied
+    /// and is no conjunct of an also unsatisfied conjunction.
+    Formula(&'a Formula),
+}
+
+impl Clause {
+    /// Evaluates this clause under the given assignment.
+    ///
+    /// Returns `Some(true)` if any literal is true, `Some(false)` if all literals
+    /// are false and `None` otherwise.
+    pub fn eval(&self, assignment: &Assignment) -> Option<bool> {
+        let mut result = Some(false);
+        for &lit in self.lits() {
+            match assignment.lit_value(lit) {
+                Some(true) => return Some(true),
+                Some(false) => (),
+                None => result = None,
+            }
+        }
+        result
+    }
+}
+
+impl XorClause {
+    /// Evaluates this XOR clause under the given assignment.
+    ///
+    /// Returns `Some(true)` if an odd number of literals is true and `None`
+    /// if any literal is unassigned.
+    pub fn eval(&self, assignment: &Assignment) -> Option<bool> {
+        let mut result = false;
+        for &lit in self.lits() {
+            result ^= assignment.lit_value(lit)?;
+        }
+        Some(result)
+    }
+}
+
+impl Formula {
+    /// Evaluates this formula under the given assignment.
+    ///
+    /// An n-ary `xor(..)` is true if an odd number of its inner formulas are true
+    /// and an n-ary `=(..)` is true if all of its inner formulas are equal.
+    /// Returns `None` if the truth value depends on unassigned variables.
+    pub fn eval(&self, assignment: &Assignment) -> Option<bool> {
+        use self::Formula::*;
+        match *self {
+            Lit(lit) => assignment.lit_value(lit),
+            Paren(ref inner) => inner.eval(assignment),
+            Neg(ref inner) => inner.eval(assignment).map(|value| !value),
+            And(ref params) => {
+                let mut result = Some(true);
+                for param in params.iter() {
+                    match param.eval(assignment) {
+                        Some(true) => (),
+                        Some(false) => return Some(false),
+                        None => result = None,
+                    }
+                }
+                result
+            }
+            Or(ref params) => {
+                let mut result = Some(false);
+                for param in params.iter() {
+                    match param.eval(assignment) {
+                        Some(true) => return Some(true),
+                        Some(false) => (),
+                        None => result = None,
+                    }
+                }
+                result
+            }
+            Xor(ref params) => {
+                let mut result = false;
+                for param in params.iter() {
+                    result ^= param.eval(assignment)?;
+                }
+                Some(result)
+            }
+            Eq(ref params) => {
+                let (mut any_true, mut any_false, mut any_unknown) = (false, false, false);
+                for param in params.iter() {
+                    match param.eval(assignment) {
+                        Some(true) => any_true = true,
+                        Some(false) => any_false = true,
+                        None => any_unknown = true,
+                    }
+                }
+                match (any_true && any_false, any_unknown) {
+                    (true, _) => Some(false),
+                    (false, true) => None,
+                    (false, false) => Some(true),
+                }
+            }
+        }
+    }
+
+    /// Returns the outermost subformula that is not satisfied under the given assignment
+    /// descending into parentheses and conjunctions.
+    fn find_unsatisfied(&self, assignment: &Assignment) -> Option<&Formula> {
+        if self.eval(assignment) == Some(true) {
+            return None;
+        }
+        match *self {
+            Formula::Paren(ref inner) => inner.find_unsatisfied(assignment),
+            Formula::And(ref params) => params
+                .iter()
+                .find_map(|param| param.find_unsatisfied(assignment)),
+            _ => Some(self),
+        }
+    }
+}
+
+impl Instance {
+    /// Checks whether the given assignment is a model of this instance.
+    ///
+    /// Clauses and formulas whose truth value depends on unassigned variables are not
+    /// satisfied. Soft clauses of `.wcnf` instances are never reported.
+    ///
+    /// Returns the first clause or subformula that is not satisfied by the assignment.
+    pub fn check_model(&self, assignment: &Assignment) -> Result<(), Falsified<'_>> {
+        let falsified = match *self {
+            Instance::Cnf {
+                ref clauses,
+                ref xors,
+                ..
+            } => clauses
+                .iter()
+                .enumerate()
+                .find(|&(_, clause)| clause.eval(assignment) != Some(true))
+                .map(|(index, clause)| Falsified::Clause(index, clause))
+                .or_else(|| {
+                    xors.iter()
+                        .enumerate()
+                        .find(|&(_, xor)| xor.eval(assignment) != Some(true))
+                        .map(|(index, xor)| Falsified::Xor(index, xor))
+                }),
+            Instance::Sat { ref formula, .. } => {
+                formula.find_unsatisfied(assignment).map(Falsified::Formula)
+            }
+            Instance::Wcnf { ref clauses, .. } => clauses
+                .iter()
+                .enumerate()
+                .filter(|&(_, clause)| clause.weight() == Weight::Hard)
+                .find(|&(_, clause)| clause.clause().eval(assignment) != Some(true))
+                .map(|(index, clause)| Falsified::Clause(index, clause.clause())),
+        };
+        match falsified {
+            Some(falsified) => Err(falsified),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::parser::parse_dimacs;
+
+    fn lit(val: i64) -> Formula {
+        Formula::lit(Lit::from_i64(val))
+    }
+
+    fn assignment(lits: &[i64]) -> Assignment {
+        let lits: Vec<Lit> = lits.iter().cloned().map(Lit::from_i64).collect();
+        Assignment::from_lits(&lits)
+    }
+
+    #[test]
+    fn assignment_values() {
+        let mut assignment = assignment(&[1, -3, 3]);
+        assert_eq!(assignment.value(Var(1)), Some(true));
+        assert_eq!(assignment.value(Var(2)), None);
+        assert_eq!(assignment.value(Var(3)), Some(true));
+        assert_eq!(assignment.lit_value(Lit::from_i64(-1)), Some(false));
+        assignment.unassign(Var(1));
+        assert_eq!(assignment.value(Var(1)), None);
+        assert_eq!(assignment.value(Var(42)), None);
+        assignment.assign(Lit::from_i64(i64::MAX));
+        assert_eq!(assignment.value(Var(i64::MAX as u64)), Some(true));
+    }
+
+    #[test]
+    fn clause_eval() {
+        let clause = Clause::from_vec(vec![Lit::from_i64(1), Lit::from_i64(-2)]);
+        assert_eq!(clause.eval(&assignment(&[-1, -2])), Some(true));
+        assert_eq!(clause.eval(&assignment(&[-1, 2])), Some(false));
+        assert_eq!(clause.eval(&assignment(&[-1])), None);
+        assert_eq!(
+            Clause::from_vec(vec![]).eval(&Assignment::new()),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn formula_eval() {
+        let xor = Formula::xor(vec![lit(1), lit(2), lit(3)]);
+        assert_eq!(xor.eval(&assignment(&[1, 2, 3])), Some(true));
+        assert_eq!(xor.eval(&assignment(&[1, 2, -3])), Some(false));
+        assert_eq!(xor.eval(&assignment(&[1, 2])), None);
+
+        let eq = Formula::eq(vec![lit(1), lit(-2), lit(3)]);
+        assert_eq!(eq.eval(&assignment(&[1, -2, 3])), Some(true));
+        assert_eq!(eq.eval(&assignment(&[-1, 2, -3])), Some(true));
+        assert_eq!(eq.eval(&assignment(&[1, 2])), Some(false));
+        assert_eq!(eq.eval(&assignment(&[1, -2])), None);
+
+        let and = Formula::and(vec![lit(1), Formula::neg(lit(2))]);
+        assert_eq!(and.eval(&assignment(&[1, -2])), Some(true));
+        assert_eq!(and.eval(&assignment(&[2])), Some(false));
+        assert_eq!(and.eval(&assignment(&[1])), None);
+
+        let or = Formula::or(vec![lit(1), lit(2)]);
+        assert_eq!(or.eval(&assignment(&[2])), Some(true));
+        assert_eq!(or.eval(&assignment(&[-1, -2])), Some(false));
+        assert_eq!(or.eval(&assignment(&[-1])), None);
+    }
+
+    #[test]
+    fn check_cnf_model() {
+        let sample = r"
+			p cnf 3 3
+			1 -2 0
+			2 3 0
+			-1 -3 0";
+        let instance = parse_dimacs(sample).expect("valid .cnf");
+        assert_eq!(instance.check_model(&assignment(&[1, 2, -3])), Ok(()));
+        let clauses = match instance {
+            Instance::Cnf { ref clauses, .. } => clauses,
+            _ => unreachable!(),
+        };
+        assert_eq!(
+            instance.check_model(&assignment(&[1, 2, 3])),
+            Err(Falsified::Clause(2, &clauses[2]))
+        );
+        assert_eq!(
+            instance.check_model(&assignment(&[1])),
+            Err(Falsified::Clause(1, &clauses[1]))
+        );
+    }
+
+    #[test]
+    fn check_xor_model() {
+        let sample = r"
+			p cnf 3 2
+			1 2 0
+			x1 -2 3 0";
+        let instance = parse_dimacs(sample).expect("valid .cnf");
+        let xor = XorClause::from_vec([1, -2, 3].iter().cloned().map(Lit::from_i64).collect());
+        assert_eq!(xor.eval(&assignment(&[1, -2, 3])), Some(true));
+        assert_eq!(xor.eval(&assignment(&[1, 2, 3])), Some(false));
+        assert_eq!(xor.eval(&assignment(&[1, 2])), None);
+        assert_eq!(
+            XorClause::from_vec(vec![]).eval(&Assignment::new()),
+            Some(false)
+        );
+        assert_eq!(instance.check_model(&assignment(&[1, -2, 3])), Ok(()));
+        assert_eq!(
+            instance.check_model(&assignment(&[1, 2, 3])),
+            Err(Falsified::Xor(0, &xor))
+        );
+    }
+
+    #[test]
+    fn check_sat_model() {
+        let sample = r"
+			p sat 3
+			(*(+(1 2) -(3)))";
+        let instance = parse_dimacs(sample).expect("valid .sat");
+        assert_eq!(instance.check_model(&assignment(&[1, -3])), Ok(()));
+        assert_eq!(
+            instance.check_model(&assignment(&[1, 3])),
+            Err(Falsified::Formula(&Formula::neg(lit(3))))
+        );
+    }
+
+    #[test]
+    fn check_wcnf_model() {
+        let sample = r"
+			h 1 2 0
+			5 -1 0";
+        let instance = parse_dimacs(sample).expect("valid .wcnf");
+        assert_eq!(instance.check_model(&assignment(&[1])), Ok(()));
+        assert_eq!(
+            instance.check_model(&assignment(&[-1, -2])),
+            Err(Falsified::Clause(
+                0,
+                &Clause::from_vec(vec![Lit::from_i64(1), Lit::from_i64(2)])
+            ))
+        );
+    }
+}
@@ -1,8 +1,19 @@
 //! Some item definitions used in instances to provide a virtual representative
 //! structure of `.cnf` or `.sat` files and their associated clauses or formula.
 
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::error;
+use std::fmt;
+use std::ops::Not;
+use std::str::FromStr;
+
+use crate::errors::ValidationError;
+use crate::lit32::TryFromLitError;
+
 /// Represents a variable within a SAT instance.
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Var(pub u64);
 
 impl Var {
@@ -10,10 +21,38 @@ impl Var {
     pub fn to_u64(self) -> u64 {
         self.0
     }
+
+    /// Returns the literal of this variable with the given sign.
+    ///
+    /// # Panics
+    ///
+    /// If the variable is `0` or greater than `i64::MAX` just like `Lit::new`.
+    pub fn lit(self, sign: Sign) -> Lit {
+        Lit::new(self, sign)
+    }
+
+    /// Returns the positive literal of this variable.
+    pub fn pos(self) -> Lit {
+        self.lit(Sign::Pos)
+    }
+
+    /// Returns the negative literal of this variable.
+    #[allow(clippy::should_implement_trait)]
+    pub fn neg(self) -> Lit {
+        self.lit(Sign::Neg)
+    }
+}
+
+impl fmt::Display for Var {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
 }
 
 /// Represents the sign of a literal.
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+///
+/// Positive signs are ordered before negative signs.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Sign {
     /// Positive sign.
     Pos,
@@ -22,7 +61,21 @@ pub enum Sign {
     Neg,
 }
 
+impl Not for Sign {
+    type Output = Sign;
+
+    fn not(self) -> Sign {
+        match self {
+            Sign::Pos => Sign::Neg,
+            Sign::Neg => Sign::Pos,
+        }
+    }
+}
+
 /// Represents a literal within clauses of formulas of a SAT instance.
+///
+/// Literals are ordered by their variables first and by their signs second
+/// so that sorting places complementary literals next to each other.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Lit(i64);
 
@@ -32,9 +85,54 @@ impl Lit {
         Lit(val)
     }
 
+    /// Creates a literal from its `i64` representant, checking that it is valid.
+    ///
+    /// Returns an error for `0`, the terminator of clauses, and for `i64::MIN`.
+    pub fn try_from_i64(val: i64) -> Result<Lit, ValidationError> {
+        match val {
+            0 => Err(ValidationError::ZeroLit),
+            i64::MIN => Err(ValidationError::LitOverflow),
+            _ => Ok(Lit(val)),
+        }
+    }
+
+    /// Checks that this literal could have been created by `Lit::try_from_i64`.
+    fn validate(self) -> Result<(), ValidationError> {
+        Lit::try_from_i64(self.0).map(|_| ())
+    }
+
+    /// Checks that this literal is valid and refers to one of the given number of variables.
+    fn validate_within(self, num_vars: u64) -> Result<(), ValidationError> {
+        self.validate()?;
+        if self.var().0 > num_vars {
+            return Err(ValidationError::VarOutOfRange {
+                lit: self,
+                num_vars,
+            });
+        }
+        Ok(())
+    }
+
+    /// Creates the literal of the given variable with the given sign.
+    ///
+    /// # Panics
+    ///
+    /// If the variable is `0`, which has no literals, or greater than `i64::MAX`.
+    pub fn new(var: Var, sign: Sign) -> Lit {
+        assert!(
+            var.0 != 0 && var.0 <= i64::MAX as u64,
+            "variable out of range"
+        );
+        let val = var.0 as i64;
+        Lit(match sign {
+            Sign::Pos => val,
+            Sign::Neg => -val,
+        })
+    }
+
     /// Returns the associated variable for this literal.
     pub fn var(self) -> Var {
-        Var(self.0.abs() as u64)
+        Var(self.0.unsigned_abs())
     }
 
     /// Returns the inner `i64` value.
@@ -49,6 +147,130 @@ impl Lit {
             _ => Sign::Neg,
         }
     }
+
+    /// Returns `true` if this literal has a positive sign.
+    pub fn is_pos(self) -> bool {
+        self.sign() == Sign::Pos
+    }
+
+    /// Returns `true` if this literal has a negative sign.
+    pub fn is_neg(self) -> bool {
+        self.sign() == Sign::Neg
+    }
+}
+
+impl Not for Lit {
+    type Output = Lit;
+
+    fn not(self) -> Lit {
+        Lit(-self.0)
+    }
+}
+
+impl Ord for Lit {
+    fn cmp(&self, other: &Lit) -> Ordering {
+        (self.var(), self.sign()).cmp(&(other.var(), other.sign()))
+    }
+}
+
+impl PartialOrd for Lit {
+    fn partial_cmp(&self, other: &Lit) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Lit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// The error returned when parsing a literal from a string fails.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ParseLitError(());
+
+impl fmt::Display for ParseLitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("invalid literal, expected a non-zero integer like `42` or `-42`")
+    }
+}
+
+impl error::Error for ParseLitError {}
+
+impl FromStr for Lit {
+    type Err = ParseLitError;
+
+    /// Parses a literal in DIMACS form, e.g. `42` or `-42`.
+    ///
+    /// Just like in `.cnf` files neither a leading `+` nor leading zeros are accepted.
+    fn from_str(input: &str) -> Result<Lit, ParseLitError> {
+        let digits = input.strip_prefix('-').unwrap_or(input);
+        if !matches!(digits.as_bytes().first(), Some(b'1'..=b'9'))
+            || !digits.bytes().all(|digit| digit.is_ascii_digit())
+        {
+            return Err(ParseLitError(()));
+        }
+        let val = i64::from_str(input).map_err(|_| ParseLitError(()))?;
+        Lit::try_from_i64(val).map_err(|_| ParseLitError(()))
+    }
+}
+
+macro_rules! impl_lit_conversions {
+    ($($int:ty),*) => {
+        $(
+            impl TryFrom<$int> for Lit {
+                type Error = ValidationError;
+
+                fn try_from(val: $int) -> Result<Lit, ValidationError> {
+                    Lit::try_from_i64(val as i64)
+                }
+            }
+        )*
+    };
+}
+
+impl_lit_conversions!(i8, i16, i32, i64);
+
+impl TryFrom<isize> for Lit {
+    type Error = ValidationError;
+
+    fn try_from(val: isize) -> Result<Lit, ValidationError> {
+        Lit::try_from_i64(i64::try_from(val).map_err(|_| ValidationError::LitOverflow)?)
+    }
+}
+
+impl From<Lit> for i64 {
+    fn from(lit: Lit) -> i64 {
+        lit.0
+    }
+}
+
+impl TryFrom<Lit> for i32 {
+    type Error = TryFromLitError;
+
+    fn try_from(lit: Lit) -> Result<i32, TryFromLitError> {
+        i32::try_from(lit.0).map_err(|_| TryFromLitError(()))
+    }
+}
+
+macro_rules! impl_var_conversions {
+    ($($uint:ty),*) => {
+        $(
+            impl From<$uint> for Var {
+                fn from(val: $uint) -> Var {
+                    Var(val as u64)
+                }
+            }
+        )*
+    };
+}
+
+impl_var_conversions!(u8, u16, u32, u64);
+
+impl From<Var> for u64 {
+    fn from(var: Var) -> u64 {
+        var.0
+    }
 }
 
 /// Represents a clause instance within a `.cnf` file.
@@ -65,17 +287,136 @@ impl Clause {
         }
     }
 
+    /// Creates a new clause from a vector of literals, checking that every literal is valid.
+    pub fn try_from_lits(lits: Vec<Lit>) -> Result<Clause, ValidationError> {
+        for lit in &lits {
+            lit.validate()?;
+        }
+        Ok(Clause::from_vec(lits))
+    }
+
+    /// Checks that all literals refer to one of the given number of variables.
+    fn validate_within(&self, num_vars: u64) -> Result<(), ValidationError> {
+        self.lits
+            .iter()
+            .try_for_each(|lit| lit.validate_within(num_vars))
+    }
+
     /// Returns the number of literals of this clause.
     pub fn len(&self) -> usize {
         self.lits.len()
     }
 
+    /// Returns `true` if this clause has no literals.
+    pub fn is_empty(&self) -> bool {
+        self.lits.is_empty()
+    }
+
     /// Returns a slice over the literals of this clause.
     pub fn lits(&self) -> &[Lit] {
         &self.lits
     }
 }
 
+/// Represents an XOR clause of an `x` line within a `.cnf` file as used by CryptoMiniSat.
+///
+/// An XOR clause is satisfied if an odd number of its literals is true, so negating
+/// any one of its literals negates the entire XOR clause.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XorClause {
+    lits: Box<[Lit]>,
+}
+
+impl XorClause {
+    /// Creates a new XOR clause from a vector of literals.
+    pub fn from_vec(lits: Vec<Lit>) -> XorClause {
+        XorClause {
+            lits: lits.into_boxed_slice(),
+        }
+    }
+
+    /// Creates a new XOR clause from a vector of literals, checking that every literal is valid.
+    pub fn try_from_lits(lits: Vec<Lit>) -> Result<XorClause, ValidationError> {
+        for lit in &lits {
+            lit.validate()?;
+        }
+        Ok(XorClause::from_vec(lits))
+    }
+
+    /// Checks that all literals refer to one of the given number of variables.
+    fn validate_within(&self, num_vars: u64) -> Result<(), ValidationError> {
+        self.lits
+            .iter()
+            .try_for_each(|lit| lit.validate_within(num_vars))
+    }
+
+    /// Returns the number of literals of this XOR clause.
+    pub fn len(&self) -> usize {
+        self.lits.len()
+    }
+
+    /// Returns `true` if this XOR clause has no literals.
+    pub fn is_empty(&self) -> bool {
+        self.lits.is_empty()
+    }
+
+    /// Returns a slice over the literals of this XOR clause.
+    pub fn lits(&self) -> &[Lit] {
+        &self.lits
+    }
+}
+
+/// Represents the weight of a clause within a `.wcnf` MaxSAT instance.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Weight {
+    /// A hard clause that must be satisfied.
+    Hard,
+
+    /// A soft clause with the given positive weight that is paid for if it is falsified.
+    Soft(u64),
+}
+
+/// Represents a weighted clause within a `.wcnf` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WeightedClause {
+    weight: Weight,
+    clause: Clause,
+}
+
+impl WeightedClause {
+    /// Creates a new weighted clause from the given weight and clause.
+    ///
+    /// # Panics
+    ///
+    /// If the weight is `Weight::Soft(0)`.
+    pub fn new(weight: Weight, clause: Clause) -> WeightedClause {
+        assert!(
+            weight != Weight::Soft(0),
+            "soft clauses must not have weight zero"
+        );
+        WeightedClause { weight, clause }
+    }
+
+    /// Creates a new weighted clause just like `new`, checking that the weight of a soft
+    /// clause is not zero.
+    pub fn try_new(weight: Weight, clause: Clause) -> Result<WeightedClause, ValidationError> {
+        if weight == Weight::Soft(0) {
+            return Err(ValidationError::ZeroWeight);
+        }
+        Ok(WeightedClause { weight, clause })
+    }
+
+    /// Returns the weight of this clause.
+    pub fn weight(&self) -> Weight {
+        self.weight
+    }
+
+    /// Returns the underlying clause.
+    pub fn clause(&self) -> &Clause {
+        &self.clause
+    }
+}
+
 /// An indirection to a `Formula` via `Box`.
 pub type FormulaBox = Box<Formula>;
 
@@ -124,6 +465,7 @@ impl Formula {
     }
 
     /// Negates the inner formula.
+    #[allow(clippy::should_implement_trait)]
     pub fn neg(inner: Formula) -> Formula {
         Formula::Neg(Box::new(inner))
     }
@@ -147,10 +489,56 @@ impl Formula {
     pub fn eq(params: Vec<Formula>) -> Formula {
         Formula::Eq(params.into_boxed_slice())
     }
+
+    /// Creates a logical and formula of all given formulas in `param` unless it is empty.
+    pub fn try_and(params: Vec<Formula>) -> Result<Formula, ValidationError> {
+        Formula::non_empty(params).map(Formula::and)
+    }
+
+    /// Creates a logical or formula of all given formulas in `param` unless it is empty.
+    pub fn try_or(params: Vec<Formula>) -> Result<Formula, ValidationError> {
+        Formula::non_empty(params).map(Formula::or)
+    }
+
+    /// Creates a logical xor formula of all given formulas in `param` unless it is empty.
+    pub fn try_xor(params: Vec<Formula>) -> Result<Formula, ValidationError> {
+        Formula::non_empty(params).map(Formula::xor)
+    }
+
+    /// Creates a logical equality formula of all given formulas in `param` unless it is empty.
+    pub fn try_eq(params: Vec<Formula>) -> Result<Formula, ValidationError> {
+        Formula::non_empty(params).map(Formula::eq)
+    }
+
+    fn non_empty(params: Vec<Formula>) -> Result<Vec<Formula>, ValidationError> {
+        if params.is_empty() {
+            return Err(ValidationError::EmptyFormula);
+        }
+        Ok(params)
+    }
+
+    /// Checks that all literals refer to one of the given number of variables
+    /// and that all n-ary formulas are non-empty.
+    fn validate_within(&self, num_vars: u64) -> Result<(), ValidationError> {
+        use self::Formula::*;
+        match *self {
+            Lit(lit) => lit.validate_within(num_vars),
+            Paren(ref inner) | Neg(ref inner) => inner.validate_within(num_vars),
+            And(ref params) | Or(ref params) | Xor(ref params) | Eq(ref params) => {
+                if params.is_empty() {
+                    return Err(ValidationError::EmptyFormula);
+                }
+                params
+                    .iter()
+                    .try_for_each(|param| param.validate_within(num_vars))
+            }
+        }
+    }
 }
 
-/// Represents a SAT instance for `.cnf` or `.sat` files.
+/// Represents a SAT instance for `.cnf`, `.sat` or `.wcnf` files.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum Instance {
     /// A `.cnf` SAT instance with clauses.
     Cnf {
@@ -159,6 +547,9 @@ pub enum Instance {
 
         /// The clauses within this `.cnf` SAT instance formula.
         clauses: Box<[Clause]>,
+
+        /// The XOR clauses of `x` lines within this `.cnf` SAT instance formula.
+        xors: Box<[XorClause]>,
     },
 
     /// A `.sat` SAT instance with an underlying formula and extensions.
@@ -172,23 +563,685 @@ pub enum Instance {
         /// The underlying formula of this SAT instance.
         formula: Formula,
     },
+
+    /// A `.wcnf` MaxSAT instance with hard and weighted soft clauses.
+    Wcnf {
+        /// The number of unique variables used within this `.wcnf` MaxSAT instance.
+        num_vars: u64,
+
+        /// The hard and soft clauses within this `.wcnf` MaxSAT instance.
+        clauses: Box<[WeightedClause]>,
+    },
 }
 
 impl Instance {
     /// Creates a new SAT instance for `.cnf` files with given clauses.
     pub fn cnf(num_vars: u64, clauses: Vec<Clause>) -> Instance {
+        Instance::cnf_with_xors(num_vars, clauses, Vec::new())
+    }
+
+    /// Creates a new SAT instance for `.cnf` files with given clauses and XOR clauses.
+    pub fn cnf_with_xors(num_vars: u64, clauses: Vec<Clause>, xors: Vec<XorClause>) -> Instance {
         Instance::Cnf {
-            num_vars: num_vars,
+            num_vars,
             clauses: clauses.into_boxed_slice(),
+            xors: xors.into_boxed_slice(),
         }
     }
 
     /// Creates a new SAT instance for `.sat` files with given extensions and an underlying formula.
     pub fn sat(num_vars: u64, extensions: Extensions, formula: Formula) -> Instance {
         Instance::Sat {
-            num_vars: num_vars,
-            extensions: extensions,
-            formula: formula,
+            num_vars,
+            extensions,
+            formula,
+        }
+    }
+
+    /// Creates a new MaxSAT instance for `.wcnf` files with given weighted clauses.
+    pub fn wcnf(num_vars: u64, clauses: Vec<WeightedClause>) -> Instance {
+        Instance::Wcnf {
+            num_vars,
+            clauses: clauses.into_boxed_slice(),
+        }
+    }
+
+    /// Creates a new SAT instance for `.cnf` files with given clauses, checking that
+    /// all literals are valid and refer to one of the `num_vars` variables.
+    pub fn try_cnf(num_vars: u64, clauses: Vec<Clause>) -> Result<Instance, ValidationError> {
+        Instance::try_cnf_with_xors(num_vars, clauses, Vec::new())
+    }
+
+    /// Creates a new SAT instance for `.cnf` files with given clauses and XOR clauses,
+    /// checking that all literals are valid and refer to one of the `num_vars` variables.
+    pub fn try_cnf_with_xors(
+        num_vars: u64,
+        clauses: Vec<Clause>,
+        xors: Vec<XorClause>,
+    ) -> Result<Instance, ValidationError> {
+        for clause in &clauses {
+            clause.validate_within(num_vars)?;
+        }
+        for xor in &xors {
+            xor.validate_within(num_vars)?;
+        }
+        Ok(Instance::cnf_with_xors(num_vars, clauses, xors))
+    }
+
+    /// Creates a new SAT instance for `.sat` files, checking that all literals of the formula
+    /// are valid and refer to one of the `num_vars` variables and that all n-ary formulas
+    /// are non-empty.
+    pub fn try_sat(
+        num_vars: u64,
+        extensions: Extensions,
+        formula: Formula,
+    ) -> Result<Instance, ValidationError> {
+        formula.validate_within(num_vars)?;
+        Ok(Instance::sat(num_vars, extensions, formula))
+    }
+
+    /// Creates a new MaxSAT instance for `.wcnf` files with given weighted clauses, checking
+    /// that all literals are valid and refer to one of the `num_vars` variables.
+    pub fn try_wcnf(
+        num_vars: u64,
+        clauses: Vec<WeightedClause>,
+    ) -> Result<Instance, ValidationError> {
+        for clause in &clauses {
+            clause.clause().validate_within(num_vars)?;
+        }
+        Ok(Instance::wcnf(num_vars, clauses))
+    }
+}
+
+/// Represents a partial assignment of truth values to the variables of an instance.
+///
+/// Variables without a truth value are unassigned which makes evaluation three-valued.
+/// Truth values are stored sparsely, so arbitrarily large variables of untrusted
+/// solver outputs are fine.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Assignment {
+    values: HashMap<Var, bool>,
+}
+
+impl Assignment {
+    /// Creates a new assignment without any assigned variables.
+    pub fn new() -> Assignment {
+        Assignment::default()
+    }
+
+    /// Creates a new assignment that makes all given literals true.
+    ///
+    /// Later literals take precedence over earlier literals of the same variable.
+    pub fn from_lits(lits: &[Lit]) -> Assignment {
+        let mut assignment = Assignment::new();
+        for &lit in lits {
+            assignment.assign(lit);
+        }
+        assignment
+    }
+
+    /// Assigns the variable of the given literal so that the literal becomes true.
+    pub fn assign(&mut self, lit: Lit) {
+        self.values.insert(lit.var(), lit.sign() == Sign::Pos);
+    }
+
+    /// Removes the truth value of the given variable.
+    pub fn unassign(&mut self, var: Var) {
+        self.values.remove(&var);
+    }
+
+    /// Returns the truth value of the given variable or `None` if it is unassigned.
+    pub fn value(&self, var: Var) -> Option<bool> {
+        self.values.get(&var).cloned()
+    }
+
+    /// Returns the truth value of the given literal or `None` if its variable is unassigned.
+    pub fn lit_value(&self, lit: Lit) -> Option<bool> {
+        self.value(lit.var())
+            .map(|value| value == (lit.sign() == Sign::Pos))
+    }
+}
+
+/// Represents the status reported by a SAT solver in its `s` line.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Status {
+    /// The instance is satisfiable, reported as `s SATISFIABLE`.
+    Satisfiable,
+
+    /// The instance is unsatisfiable, reported as `s UNSATISFIABLE`.
+    Unsatisfiable,
+
+    /// The solver could not decide the instance, reported as `s UNKNOWN`.
+    Unknown,
+}
+
+/// Represents the output of a SAT solver in the format of the SAT competitions.
+///
+/// The output consists of the status line and, for satisfiable instances,
+/// the model given by `v` lines that is terminated by `0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolverOutput {
+    status: Status,
+    model: Box<[Lit]>,
+}
+
+impl SolverOutput {
+    /// Creates a new solver output with the given status and model.
+    pub fn new(status: Status, model: Vec<Lit>) -> SolverOutput {
+        SolverOutput {
+            status,
+            model: model.into_boxed_slice(),
+        }
+    }
+
+    /// Returns the reported status.
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    /// Returns the literals of the reported model in the order they were given.
+    pub fn model(&self) -> &[Lit] {
+        &self.model
+    }
+
+    /// Returns the reported model as assignment.
+    pub fn assignment(&self) -> Assignment {
+        Assignment::from_lits(&self.model)
+    }
+}
+
+/// Represents a step of a DRAT proof.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProofStep {
+    /// Adds the clause to the clause database.
+    Add(Clause),
+
+    /// Deletes the clause from the clause database.
+    Delete(Clause),
+}
+
+/// Represents a step of a LRAT proof.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LratStep {
+    /// Adds a clause that is implied by the given hints.
+    Add {
+        /// The identifier of the added clause.
+        id: u64,
+
+        /// The added clause.
+        clause: Clause,
+
+        /// The identifiers of the clauses that become unit in order to derive the added clause.
+        /// Negative hints denote the clauses containing the negated pivot of a RAT step.
+        hints: Box<[i64]>,
+    },
+
+    /// Deletes the clauses with the given identifiers.
+    Delete {
+        /// The identifiers of the deleted clauses.
+        ids: Box<[u64]>,
+    },
+}
+
+impl LratStep {
+    /// Creates a new step adding the clause with the given identifier and hints.
+    pub fn add(id: u64, clause: Clause, hints: Vec<i64>) -> LratStep {
+        LratStep::Add {
+            id,
+            clause,
+            hints: hints.into_boxed_slice(),
+        }
+    }
+
+    /// Creates a new step deleting the clauses with the given identifiers.
+    pub fn delete(ids: Vec<u64>) -> LratStep {
+        LratStep::Delete {
+            ids: ids.into_boxed_slice(),
+        }
+    }
+}
+
+/// Represents the quantifier of a block of variables within a `.qdimacs` file.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Quantifier {
+    /// The existential quantifier denoted by `e`.
+    Exists,
+
+    /// The universal quantifier denoted by `a`.
+    Forall,
+}
+
+/// Represents a block of equally quantified variables within the prefix of a `.qdimacs` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuantifierBlock {
+    quantifier: Quantifier,
+    vars: Box<[Var]>,
+}
+
+impl QuantifierBlock {
+    /// Creates a new quantifier block of the given variables.
+    pub fn new(quantifier: Quantifier, vars: Vec<Var>) -> QuantifierBlock {
+        QuantifierBlock {
+            quantifier,
+            vars: vars.into_boxed_slice(),
+        }
+    }
+
+    /// Returns the quantifier of this block.
+    pub fn quantifier(&self) -> Quantifier {
+        self.quantifier
+    }
+
+    /// Returns a slice over the variables of this block.
+    pub fn vars(&self) -> &[Var] {
+        &self.vars
+    }
+}
+
+/// Represents a quantified boolean formula of a `.qdimacs` file.
+///
+/// The formula consists of an ordered quantifier prefix from the outermost to the innermost
+/// quantifier block and a matrix of clauses in conjunctive normal form.
+/// Variables that do not occure within the prefix are implicitly existentially quantified
+/// at the outermost level.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QdimacsInstance {
+    num_vars: u64,
+    prefix: Box<[QuantifierBlock]>,
+    clauses: Box<[Clause]>,
+}
+
+impl QdimacsInstance {
+    /// Creates a new `.qdimacs` instance with the given quantifier prefix and clauses.
+    pub fn new(
+        num_vars: u64,
+        prefix: Vec<QuantifierBlock>,
+        clauses: Vec<Clause>,
+    ) -> QdimacsInstance {
+        QdimacsInstance {
+            num_vars,
+            prefix: prefix.into_boxed_slice(),
+            clauses: clauses.into_boxed_slice(),
+        }
+    }
+
+    /// Returns the number of variables declared in the problem line.
+    pub fn num_vars(&self) -> u64 {
+        self.num_vars
+    }
+
+    /// Returns the quantifier prefix from the outermost to the innermost block.
+    pub fn prefix(&self) -> &[QuantifierBlock] {
+        &self.prefix
+    }
+
+    /// Returns the clauses of the matrix.
+    pub fn clauses(&self) -> &[Clause] {
+        &self.clauses
+    }
+}
+
+/// Represents a single step of an incremental `.icnf` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IncrementalStep {
+    /// Adds a clause to the formula, written as an ordinary clause line.
+    Add(Clause),
+
+    /// Solves the formula of all clauses added so far under the given assumptions,
+    /// written as an `a` line.
+    Solve(Box<[Lit]>),
+}
+
+impl IncrementalStep {
+    /// Creates a new step adding a clause of the given literals.
+    pub fn add(lits: Vec<Lit>) -> IncrementalStep {
+        IncrementalStep::Add(Clause::from_vec(lits))
+    }
+
+    /// Creates a new step solving under the given assumptions.
+    pub fn solve(assumptions: Vec<Lit>) -> IncrementalStep {
+        IncrementalStep::Solve(assumptions.into_boxed_slice())
+    }
+}
+
+/// Represents an incremental SAT problem of an `.icnf` file with a `p inccnf` problem line.
+///
+/// In contrast to `.cnf` files the problem line declares no numbers and clauses are
+/// interleaved with the assumptions of the solver calls in the order of their steps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IncrementalInstance {
+    steps: Box<[IncrementalStep]>,
+}
+
+impl IncrementalInstance {
+    /// Creates a new incremental instance with the given steps.
+    pub fn new(steps: Vec<IncrementalStep>) -> IncrementalInstance {
+        IncrementalInstance {
+            steps: steps.into_boxed_slice(),
+        }
+    }
+
+    /// Returns the steps in the order of the file.
+    pub fn steps(&self) -> &[IncrementalStep] {
+        &self.steps
+    }
+
+    /// Returns the largest variable used within any step or `0` if there is none.
+    pub fn max_var(&self) -> u64 {
+        self.steps
+            .iter()
+            .flat_map(|step| match step {
+                IncrementalStep::Add(clause) => clause.lits(),
+                IncrementalStep::Solve(assumptions) => assumptions,
+            })
+            .map(|lit| lit.var().to_u64())
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Represents the problem kind of a DIMACS graph file.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GraphKind {
+    /// A graph problem declared by `p edge`.
+    Edge,
+
+    /// A graph colouring problem declared by `p col`.
+    Col,
+}
+
+/// Represents an undirected edge between two vertices of a graph.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Edge {
+    u: u64,
+    v: u64,
+}
+
+impl Edge {
+    /// Creates a new edge between the vertices `u` and `v`.
+    pub fn new(u: u64, v: u64) -> Edge {
+        Edge { u, v }
+    }
+
+    /// Returns the vertices of this edge in the order they were given.
+    pub fn vertices(self) -> (u64, u64) {
+        (self.u, self.v)
+    }
+}
+
+/// Represents the weight of a vertex given by a `n` line of a graph file.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct VertexWeight {
+    vertex: u64,
+    weight: u64,
+}
+
+impl VertexWeight {
+    /// Creates a new weight for the given vertex.
+    pub fn new(vertex: u64, weight: u64) -> VertexWeight {
+        VertexWeight { vertex, weight }
+    }
+
+    /// Returns the weighted vertex.
+    pub fn vertex(self) -> u64 {
+        self.vertex
+    }
+
+    /// Returns the weight of the vertex.
+    pub fn weight(self) -> u64 {
+        self.weight
+    }
+}
+
+/// Represents an undirected graph of a DIMACS `p edge` or `p col` file.
+///
+/// Vertices are numbered from 1 up to the number of vertices.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Graph {
+    kind: GraphKind,
+    num_vertices: u64,
+    edges: Box<[Edge]>,
+    weights: Box<[VertexWeight]>,
+}
+
+impl Graph {
+    /// Creates a new graph with the given edges and vertex weights.
+    pub fn new(
+        kind: GraphKind,
+        num_vertices: u64,
+        edges: Vec<Edge>,
+        weights: Vec<VertexWeight>,
+    ) -> Graph {
+        Graph {
+            kind,
+            num_vertices,
+            edges: edges.into_boxed_slice(),
+            weights: weights.into_boxed_slice(),
+        }
+    }
+
+    /// Returns the problem kind declared in the problem line.
+    pub fn kind(&self) -> GraphKind {
+        self.kind
+    }
+
+    /// Returns the number of vertices declared in the problem line.
+    pub fn num_vertices(&self) -> u64 {
+        self.num_vertices
+    }
+
+    /// Returns the edges of this graph.
+    pub fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+
+    /// Returns the vertex weights of this graph in the order they were given.
+    pub fn weights(&self) -> &[VertexWeight] {
+        &self.weights
+    }
+}
+
+/// Represents a weighted arc of a shortest path problem given by an `a u v w` line.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct WeightedArc {
+    from: u64,
+    to: u64,
+    weight: u64,
+}
+
+impl WeightedArc {
+    /// Creates a new arc from node `from` to node `to` with the given weight.
+    pub fn new(from: u64, to: u64, weight: u64) -> WeightedArc {
+        WeightedArc { from, to, weight }
+    }
+
+    /// Returns the tail node of this arc.
+    pub fn from(self) -> u64 {
+        self.from
+    }
+
+    /// Returns the head node of this arc.
+    pub fn to(self) -> u64 {
+        self.to
+    }
+
+    /// Returns the weight of this arc.
+    pub fn weight(self) -> u64 {
+        self.weight
+    }
+}
+
+/// Represents an arc of a maximum flow problem given by an `a u v cap` line.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CapacityArc {
+    from: u64,
+    to: u64,
+    capacity: u64,
+}
+
+impl CapacityArc {
+    /// Creates a new arc from node `from` to node `to` with the given capacity.
+    pub fn new(from: u64, to: u64, capacity: u64) -> CapacityArc {
+        CapacityArc { from, to, capacity }
+    }
+
+    /// Returns the tail node of this arc.
+    pub fn from(self) -> u64 {
+        self.from
+    }
+
+    /// Returns the head node of this arc.
+    pub fn to(self) -> u64 {
+        self.to
+    }
+
+    /// Returns the capacity of this arc.
+    pub fn capacity(self) -> u64 {
+        self.capacity
+    }
+}
+
+/// Represents an arc of a minimum cost flow problem given by an `a u v low cap cost` line.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CostArc {
+    from: u64,
+    to: u64,
+    lower: u64,
+    capacity: u64,
+    cost: i64,
+}
+
+impl CostArc {
+    /// Creates a new arc from node `from` to node `to` with the given flow bounds and cost per unit.
+    pub fn new(from: u64, to: u64, lower: u64, capacity: u64, cost: i64) -> CostArc {
+        CostArc {
+            from,
+            to,
+            lower,
+            capacity,
+            cost,
+        }
+    }
+
+    /// Returns the tail node of this arc.
+    pub fn from(self) -> u64 {
+        self.from
+    }
+
+    /// Returns the head node of this arc.
+    pub fn to(self) -> u64 {
+        self.to
+    }
+
+    /// Returns the minimum flow of this arc.
+    pub fn lower(self) -> u64 {
+        self.lower
+    }
+
+    /// Returns the maximum flow of this arc.
+    pub fn capacity(self) -> u64 {
+        self.capacity
+    }
+
+    /// Returns the cost per unit of flow of this arc.
+    pub fn cost(self) -> i64 {
+        self.cost
+    }
+}
+
+/// Represents the supply of a node of a minimum cost flow problem given by a `n id flow` line.
+///
+/// Negative supplies denote demands.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NodeSupply {
+    node: u64,
+    supply: i64,
+}
+
+impl NodeSupply {
+    /// Creates a new supply for the given node.
+    pub fn new(node: u64, supply: i64) -> NodeSupply {
+        NodeSupply { node, supply }
+    }
+
+    /// Returns the supplying node.
+    pub fn node(self) -> u64 {
+        self.node
+    }
+
+    /// Returns the supply of the node.
+    pub fn supply(self) -> i64 {
+        self.supply
+    }
+}
+
+/// Represents a network problem of the DIMACS implementation challenges.
+///
+/// Nodes are numbered from 1 up to the number of nodes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Network {
+    /// A shortest path problem declared by `p sp`.
+    ShortestPath {
+        /// The number of nodes declared in the problem line.
+        num_nodes: u64,
+
+        /// The weighted arcs of the network.
+        arcs: Box<[WeightedArc]>,
+    },
+
+    /// A maximum flow problem declared by `p max`.
+    MaxFlow {
+        /// The number of nodes declared in the problem line.
+        num_nodes: u64,
+
+        /// The source node.
+        source: u64,
+
+        /// The sink node.
+        sink: u64,
+
+        /// The arcs of the network with their capacities.
+        arcs: Box<[CapacityArc]>,
+    },
+
+    /// A minimum cost flow problem declared by `p min`.
+    MinCostFlow {
+        /// The number of nodes declared in the problem line.
+        num_nodes: u64,
+
+        /// The supplies of nodes in the order they were given.
+        supplies: Box<[NodeSupply]>,
+
+        /// The arcs of the network with their flow bounds and costs.
+        arcs: Box<[CostArc]>,
+    },
+}
+
+impl Network {
+    /// Creates a new shortest path problem.
+    pub fn shortest_path(num_nodes: u64, arcs: Vec<WeightedArc>) -> Network {
+        Network::ShortestPath {
+            num_nodes,
+            arcs: arcs.into_boxed_slice(),
+        }
+    }
+
+    /// Creates a new maximum flow problem from `source` to `sink`.
+    pub fn max_flow(num_nodes: u64, source: u64, sink: u64, arcs: Vec<CapacityArc>) -> Network {
+        Network::MaxFlow {
+            num_nodes,
+            source,
+            sink,
+            arcs: arcs.into_boxed_slice(),
+        }
+    }
+
+    /// Creates a new minimum cost flow problem.
+    pub fn min_cost_flow(num_nodes: u64, supplies: Vec<NodeSupply>, arcs: Vec<CostArc>) -> Network {
+        Network::MinCostFlow {
+            num_nodes,
+            supplies: supplies.into_boxed_slice(),
+            arcs: arcs.into_boxed_slice(),
         }
     }
 }
@@ -204,3 +1257,154 @@ bitflags! {
         const EQ   = 0b00000010;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lit(val: i64) -> Lit {
+        Lit::try_from_i64(val).expect("valid literal")
+    }
+
+    #[test]
+    fn lit_algebra() {
+        let var = Var(3);
+        assert_eq!(var.pos(), lit(3));
+        assert_eq!(var.neg(), lit(-3));
+        assert_eq!(Lit::new(var, Sign::Neg), lit(-3));
+        assert_eq!(!lit(-3), lit(3));
+        assert_eq!(!Sign::Pos, Sign::Neg);
+        assert_eq!(lit(-3).sign(), !lit(3).sign());
+        assert!(lit(3).is_pos() && !lit(3).is_neg());
+        assert!(lit(-3).is_neg() && !lit(-3).is_pos());
+    }
+
+    #[test]
+    fn lit_order() {
+        let mut lits = vec![lit(2), lit(-1), lit(-2), lit(3), lit(1)];
+        lits.sort();
+        assert_eq!(lits, vec![lit(1), lit(-1), lit(2), lit(-2), lit(3)]);
+        assert!(Var(1) < Var(2));
+        assert!(Sign::Pos < Sign::Neg);
+    }
+
+    #[test]
+    fn lit_display_and_from_str() {
+        assert_eq!(lit(-42).to_string(), "-42");
+        assert_eq!(Var(42).to_string(), "42");
+        assert_eq!("-42".parse(), Ok(lit(-42)));
+        for invalid in &["+7", "07", "-0", "-", "", "- 7", "-9223372036854775808"] {
+            assert_eq!(invalid.parse::<Lit>(), Err(ParseLitError(())));
+        }
+        assert_eq!("0".parse::<Lit>(), Err(ParseLitError(())));
+        assert_eq!("x1".parse::<Lit>(), Err(ParseLitError(())));
+        assert_eq!(lit(-42).to_string().parse(), Ok(lit(-42)));
+    }
+
+    #[test]
+    fn lit_int_conversions() {
+        assert_eq!(Lit::try_from(-5i8), Ok(lit(-5)));
+        assert_eq!(Lit::try_from(7i32), Ok(lit(7)));
+        assert_eq!(Lit::try_from(0i64), Err(ValidationError::ZeroLit));
+        assert_eq!(Lit::try_from(i64::MIN), Err(ValidationError::LitOverflow));
+        assert_eq!(Lit::try_from(-9isize), Ok(lit(-9)));
+        assert_eq!(i64::from(lit(-42)), -42);
+        assert_eq!(i32::try_from(lit(-42)), Ok(-42));
+        assert_eq!(i32::try_from(lit(i64::MAX)), Err(TryFromLitError(())));
+        assert_eq!(Var::from(42u8), Var(42));
+        assert_eq!(u64::from(Var(42)), 42);
+    }
+
+    #[test]
+    #[should_panic(expected = "variable out of range")]
+    fn lit_of_var_zero() {
+        Lit::new(Var(0), Sign::Pos);
+    }
+
+    #[test]
+    fn validate_lits() {
+        assert_eq!(Lit::try_from_i64(-42), Ok(Lit::from_i64(-42)));
+        assert_eq!(Lit::try_from_i64(0), Err(ValidationError::ZeroLit));
+        assert_eq!(
+            Lit::try_from_i64(i64::MIN),
+            Err(ValidationError::LitOverflow)
+        );
+        assert_eq!(
+            Clause::try_from_lits(vec![lit(1), Lit::from_i64(0)]),
+            Err(ValidationError::ZeroLit)
+        );
+        assert_eq!(
+            Clause::try_from_lits(vec![lit(1), lit(-2)]),
+            Ok(Clause::from_vec(vec![lit(1), lit(-2)]))
+        );
+    }
+
+    #[test]
+    fn validate_instances() {
+        let clauses = vec![
+            Clause::from_vec(vec![lit(1), lit(-2)]),
+            Clause::from_vec(vec![lit(3)]),
+        ];
+        assert_eq!(
+            Instance::try_cnf(3, clauses.clone()),
+            Ok(Instance::cnf(3, clauses.clone()))
+        );
+        assert_eq!(
+            Instance::try_cnf(2, clauses.clone()),
+            Err(ValidationError::VarOutOfRange {
+                lit: lit(3),
+                num_vars: 2
+            })
+        );
+        let xors = vec![XorClause::from_vec(vec![lit(-1), lit(4)])];
+        assert_eq!(
+            Instance::try_cnf_with_xors(4, clauses.clone(), xors.clone()),
+            Ok(Instance::cnf_with_xors(4, clauses.clone(), xors.clone()))
+        );
+        assert_eq!(
+            Instance::try_cnf_with_xors(3, clauses.clone(), xors),
+            Err(ValidationError::VarOutOfRange {
+                lit: lit(4),
+                num_vars: 3
+            })
+        );
+        assert_eq!(
+            XorClause::try_from_lits(vec![lit(1), Lit::from_i64(0)]),
+            Err(ValidationError::ZeroLit)
+        );
+        let weighted = clauses
+            .into_iter()
+            .map(|clause| WeightedClause::new(Weight::Hard, clause))
+            .collect();
+        assert!(Instance::try_wcnf(2, weighted).is_err());
+        assert_eq!(
+            WeightedClause::try_new(Weight::Soft(0), Clause::from_vec(vec![lit(1)])),
+            Err(ValidationError::ZeroWeight)
+        );
+    }
+
+    #[test]
+    fn validate_formulas() {
+        assert_eq!(Formula::try_and(vec![]), Err(ValidationError::EmptyFormula));
+        assert_eq!(
+            Formula::try_or(vec![Formula::lit(lit(1))]),
+            Ok(Formula::or(vec![Formula::lit(lit(1))]))
+        );
+        let formula = Formula::neg(Formula::xor(vec![
+            Formula::lit(lit(1)),
+            Formula::eq(vec![]),
+        ]));
+        assert_eq!(
+            Instance::try_sat(1, Extensions::XOR | Extensions::EQ, formula),
+            Err(ValidationError::EmptyFormula)
+        );
+        let formula = Formula::and(vec![Formula::lit(lit(1)), Formula::lit(lit(-2))]);
+        assert_eq!(
+            Instance::try_sat(1, Extensions::NONE, formula),
+            Err(ValidationError::VarOutOfRange {
+                lit: lit(-2),
+                num_vars: 1
+            })
+        );
+    }
+}
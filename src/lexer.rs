@@ -1,3 +1,6 @@
+use std::fmt;
+use std::io;
+
 use crate::errors::*;
 
 use crate::errors::ErrorKind::*;
@@ -5,19 +8,21 @@ use crate::errors::ErrorKind::*;
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct Token {
     pub loc: Loc,
+    pub span: Span,
     pub kind: TokenKind,
 }
 
 impl Token {
-    pub fn new(loc: Loc, kind: TokenKind) -> Token {
-        Token {
-            loc: loc,
-            kind: kind,
-        }
+    pub fn new(loc: Loc, span: Span, kind: TokenKind) -> Token {
+        Token { loc, span, kind }
     }
 }
 
+/// The different kinds of tokens of `.cnf` and `.sat` files.
+///
+/// Further token kinds may be added when new file formats are supported.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum TokenKind {
     /// Represents an entire 'c foo bar\n' line
     Comment,
@@ -57,14 +62,33 @@ use self::TokenKind::*;
 impl TokenKind {
     /// Returns `true` if this `TokenKind` is relevant for parsing purposes.
     pub fn is_relevant(self) -> bool {
-        match self {
-            Comment => false,
-            _ => true,
+        !matches!(self, Comment)
+    }
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Comment => f.write_str("comment"),
+            Nat(val) => write!(f, "`{}`", val),
+            Zero => f.write_str("`0`"),
+            Plus => f.write_str("`+`"),
+            Minus => f.write_str("`-`"),
+            Star => f.write_str("`*`"),
+            Eq => f.write_str("`=`"),
+            Open => f.write_str("`(`"),
+            Close => f.write_str("`)`"),
+            Ident(ident) => write!(f, "`{}`", ident),
+            EndOfFile => f.write_str("end of file"),
         }
     }
 }
 
+/// The known keywords of `.cnf` and `.sat` files.
+///
+/// Further keywords may be added when new file formats are supported.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum Ident {
     /// Represents a 'p' keyword
     Problem,
@@ -86,13 +110,109 @@ pub enum Ident {
 
     /// Used as problem-kind parameter in problem lines to denote a CNF problem with the Eq and Xor extensions.
     Satex,
+
+    /// Used as problem-kind parameter in problem lines to denote a weighted MaxSAT problem.
+    Wcnf,
+
+    /// Represents a 'h' keyword that marks hard clauses of weighted MaxSAT problems.
+    Hard,
+
+    /// Represents an 'e' keyword that starts an existentially quantified block of variables
+    /// in `.qdimacs` files or an edge line in graph files.
+    E,
+
+    /// Represents an 'a' keyword that starts a universally quantified block of variables
+    /// in `.qdimacs` files or an arc line in network files.
+    A,
+
+    /// Represents a 'n' keyword that starts a vertex line in graph files.
+    Node,
+
+    /// Used as problem-kind parameter in problem lines to denote a graph problem.
+    Edge,
+
+    /// Used as problem-kind parameter in problem lines to denote a graph colouring problem.
+    Col,
+
+    /// Used as problem-kind parameter in problem lines to denote a shortest path problem.
+    Sp,
+
+    /// Used as problem-kind parameter in problem lines to denote a maximum flow problem.
+    Max,
+
+    /// Used as problem-kind parameter in problem lines to denote a minimum cost flow problem.
+    Min,
+
+    /// Represents a 's' keyword that designates the source node of a maximum flow problem
+    /// or starts the status line of a solver output.
+    S,
+
+    /// Represents a 't' keyword that designates the sink node of a maximum flow problem.
+    Sink,
+
+    /// Represents a 'v' keyword that starts a line of the model of a solver output.
+    Values,
+
+    /// Represents the `SATISFIABLE` status of a solver output.
+    Satisfiable,
+
+    /// Represents the `UNSATISFIABLE` status of a solver output.
+    Unsatisfiable,
+
+    /// Represents the `UNKNOWN` status of a solver output.
+    Unknown,
+
+    /// Represents a 'd' keyword that marks the deletion of clauses in proofs.
+    Delete,
+
+    /// Used as problem-kind parameter in problem lines to denote an incremental CNF problem.
+    Inccnf,
+
+    /// Represents an 'x' keyword that starts an XOR clause in `.cnf` files, e.g. `x1 -2 3 0`.
+    X,
 }
 use self::Ident::*;
 
+impl fmt::Display for Ident {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            Problem => "p",
+            Xor => "xor",
+            Cnf => "cnf",
+            Sat => "sat",
+            Satx => "satx",
+            Sate => "sate",
+            Satex => "satex",
+            Wcnf => "wcnf",
+            Hard => "h",
+            E => "e",
+            A => "a",
+            Node => "n",
+            Edge => "edge",
+            Col => "col",
+            Sp => "sp",
+            Max => "max",
+            Min => "min",
+            S => "s",
+            Sink => "t",
+            Values => "v",
+            Satisfiable => "SATISFIABLE",
+            Unsatisfiable => "UNSATISFIABLE",
+            Unknown => "UNKNOWN",
+            Delete => "d",
+            Inccnf => "inccnf",
+            X => "x",
+        })
+    }
+}
+
+/// The length of the longest keyword, `UNSATISFIABLE`.
+const MAX_KEYWORD_LEN: usize = 13;
+
 #[derive(Debug, Clone)]
 pub struct Lexer<I>
 where
-    I: Iterator<Item = u8>,
+    I: Iterator<Item = io::Result<u8>>,
 {
     /// input iterator
     input: I,
@@ -108,35 +228,94 @@ where
 
     /// represents the current `Loc` within the stream
     cloc: Loc,
+
+    /// the byte offset of the next iterated item
+    noff: u64,
+
+    /// the byte offset of the current byte within the stream
+    coff: u64,
+
+    /// the number of bytes read from the input iterator
+    read: u64,
+
+    /// an error of the input iterator that is yet to be reported
+    io_error: Option<ParseError>,
+
+    /// set after the input iterator failed since no further input is read afterwards
+    failed: bool,
+
+    /// whether an `x` at the start of a line is lexed as the start of an XOR clause
+    xor_lines: bool,
+
+    /// whether no token has been scanned on the current line yet
+    line_start: bool,
 }
 
 impl<I> Lexer<I>
 where
-    I: Iterator<Item = u8>,
+    I: Iterator<Item = io::Result<u8>>,
 {
     pub fn from(input: I) -> Lexer<I> {
+        Lexer::starting_at(input, Loc::new(1, 0), 0)
+    }
+
+    /// Creates a lexer for input that starts at the given location and byte offset
+    /// of a larger input, e.g. at the start of a line.
+    pub fn starting_at(input: I, loc: Loc, offset: u64) -> Lexer<I> {
         let mut lex = Lexer {
-            input: input,
+            input,
             buffer: Vec::new(),
             peek: b'\0',
-            nloc: Loc::new(1, 0),
-            cloc: Loc::new(1, 0),
+            nloc: loc,
+            cloc: loc,
+            noff: offset,
+            coff: offset,
+            read: offset,
+            io_error: None,
+            failed: false,
+            xor_lines: false,
+            line_start: true,
         };
         lex.bump();
         lex
     }
 
+    /// Lexes an `x` at the start of a line as `Ident::X` even if it is directly
+    /// followed by a literal, as in the XOR clauses of `.cnf` files.
+    pub fn lex_xor_lines(&mut self) {
+        self.xor_lines = true;
+    }
+
     fn bump_opt(&mut self) -> Option<u8> {
-        if let Some(peeked) = self.input.next() {
-            self.peek = peeked;
-            if peeked == b'\n' {
-                self.cloc.bump_line()
-            } else {
-                self.cloc.bump_col()
+        if self.failed {
+            return None;
+        }
+        match self.input.next() {
+            Some(Ok(peeked)) => {
+                self.peek = peeked;
+                self.coff = self.read;
+                self.read += 1;
+                if peeked == b'\n' {
+                    self.cloc.bump_line()
+                } else {
+                    self.cloc.bump_col()
+                }
+                Some(peeked)
+            }
+            Some(Err(err)) => {
+                let mut loc = self.cloc;
+                loc.bump_col();
+                self.coff = self.read;
+                self.io_error = Some(
+                    ParseError::new(loc, Io(err.into())).with_span(Span::new(self.read, self.read)),
+                );
+                self.failed = true;
+                None
+            }
+            None => {
+                self.coff = self.read;
+                None
             }
-            Some(peeked)
-        } else {
-            None
         }
     }
 
@@ -145,12 +324,16 @@ where
         self.peek
     }
 
+    fn span(&self) -> Span {
+        Span::new(self.noff, self.coff)
+    }
+
     fn mk_token(&self, kind: TokenKind) -> Token {
-        Token::new(self.nloc, kind)
+        Token::new(self.nloc, self.span(), kind)
     }
 
     fn mk_error(&self, kind: ErrorKind) -> ParseError {
-        ParseError::new(self.nloc, kind)
+        ParseError::new(self.nloc, kind).with_span(self.span())
     }
 
     fn tok(&self, kind: TokenKind) -> Result<Token> {
@@ -182,11 +365,15 @@ where
         self.err(UnknownKeyword)
     }
 
-    fn scan_keyword(&mut self) -> Result<Token> {
+    fn scan_keyword(&mut self, line_start: bool) -> Result<Token> {
+        let xor_line = self.xor_lines && line_start && self.peek == b'x';
         self.buffer.clear();
         self.buffer.push(self.peek);
         while self.bump().is_ascii_alphanumeric() {
-            if self.buffer.len() < 5 {
+            if xor_line && self.buffer == b"x" && self.peek.is_ascii_digit() {
+                break;
+            }
+            if self.buffer.len() < MAX_KEYWORD_LEN {
                 self.buffer.push(self.peek);
             } else {
                 return self.unknown_keyword();
@@ -201,13 +388,32 @@ where
             b"satx" => self.tok(Ident(Satx)),
             b"satex" => self.tok(Ident(Satex)),
             b"xor" => self.tok(Ident(Xor)),
+            b"wcnf" => self.tok(Ident(Wcnf)),
+            b"h" => self.tok(Ident(Hard)),
+            b"e" => self.tok(Ident(E)),
+            b"a" => self.tok(Ident(A)),
+            b"n" => self.tok(Ident(Node)),
+            b"edge" => self.tok(Ident(Edge)),
+            b"col" => self.tok(Ident(Col)),
+            b"sp" => self.tok(Ident(Sp)),
+            b"max" => self.tok(Ident(Max)),
+            b"min" => self.tok(Ident(Min)),
+            b"s" => self.tok(Ident(S)),
+            b"t" => self.tok(Ident(Sink)),
+            b"v" => self.tok(Ident(Values)),
+            b"SATISFIABLE" => self.tok(Ident(Satisfiable)),
+            b"UNSATISFIABLE" => self.tok(Ident(Unsatisfiable)),
+            b"UNKNOWN" => self.tok(Ident(Unknown)),
+            b"d" => self.tok(Ident(Delete)),
+            b"inccnf" => self.tok(Ident(Inccnf)),
+            b"x" if xor_line => self.tok(Ident(X)),
             _ => self.err(UnknownKeyword),
         }
     }
 
     fn scan_nat(&mut self) -> Result<Token> {
         let mut val = if self.peek.is_ascii_digit() {
-            (self.peek - b'0') as u64
+            Some((self.peek - b'0') as u64)
         } else {
             panic!("expected a digit to base 10: (0...9)")
         };
@@ -216,30 +422,48 @@ where
             if !peeked.is_ascii_digit() {
                 break;
             }
-            val *= 10;
-            val += (peeked - b'0') as u64;
+            // Keeps scanning after an overflow so that the error spans the entire number.
+            val = val
+                .and_then(|val| val.checked_mul(10))
+                .and_then(|val| val.checked_add((peeked - b'0') as u64));
+        }
+        match val {
+            Some(val) => self.tok(Nat(val)),
+            None => self.err(NatOverflow),
         }
-        self.tok(Nat(val))
     }
 
     fn skip_whitespace(&mut self) {
         while self.peek.is_ascii_whitespace() {
+            if self.peek == b'\n' {
+                self.line_start = true;
+            }
             self.bump();
         }
     }
 
     fn update_nloc(&mut self) {
         self.nloc = self.cloc;
+        self.noff = self.coff;
     }
 
     fn next_token(&mut self) -> Option<Result<Token>> {
+        let token = self.scan_token();
+        match self.io_error.take() {
+            Some(err) => Some(Err(err)),
+            None => token,
+        }
+    }
+
+    fn scan_token(&mut self) -> Option<Result<Token>> {
         self.skip_whitespace();
         if self.peek == b'\0' {
             return None;
         }
         self.update_nloc();
+        let line_start = std::mem::replace(&mut self.line_start, false);
         Some(match self.peek {
-            b'A'..=b'Z' | b'a'..=b'z' => self.scan_keyword(),
+            b'A'..=b'Z' | b'a'..=b'z' => self.scan_keyword(line_start),
 
             b'1'..=b'9' => self.scan_nat(),
 
@@ -261,7 +485,7 @@ where
 
 impl<I> Iterator for Lexer<I>
 where
-    I: Iterator<Item = u8>,
+    I: Iterator<Item = io::Result<u8>>,
 {
     type Item = Result<Token>;
 
@@ -273,25 +497,39 @@ where
 #[derive(Debug, Clone)]
 pub struct ValidLexer<I>
 where
-    I: Iterator<Item = u8>,
+    I: Iterator<Item = io::Result<u8>>,
 {
     input: Lexer<I>,
 }
 
 impl<I> ValidLexer<I>
 where
-    I: Iterator<Item = u8>,
+    I: Iterator<Item = io::Result<u8>>,
 {
     pub fn from(input: I) -> ValidLexer<I> {
         ValidLexer {
             input: Lexer::from(input),
         }
     }
+
+    /// Creates a lexer for input that starts at the given location and byte offset
+    /// of a larger input.
+    #[cfg(feature = "parallel")]
+    pub fn starting_at(input: I, loc: Loc, offset: u64) -> ValidLexer<I> {
+        ValidLexer {
+            input: Lexer::starting_at(input, loc, offset),
+        }
+    }
+
+    /// Lexes an `x` at the start of a line as `Ident::X`, see `Lexer::lex_xor_lines`.
+    pub fn lex_xor_lines(&mut self) {
+        self.input.lex_xor_lines()
+    }
 }
 
 impl<I> Iterator for ValidLexer<I>
 where
-    I: Iterator<Item = u8>,
+    I: Iterator<Item = io::Result<u8>>,
 {
     type Item = Result<Token>;
 
@@ -328,49 +566,141 @@ mod tests {
 			-3 4 0
 			5 -6 7 0
 			-7 -8 -9 0";
-        let mut lexer = Lexer::from(sample.bytes());
+        let mut lexer = Lexer::from(sample.bytes().map(Ok));
 
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(2, 4), Comment))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(3, 4), Comment))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(4, 4), Comment))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(5, 4), Comment))));
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(2, 4), Span::new(4, 29), Comment)))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(3, 4), Span::new(33, 59), Comment)))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(4, 4), Span::new(63, 86), Comment)))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(5, 4), Span::new(90, 107), Comment)))
+        );
 
         assert_eq!(
             lexer.next(),
-            Some(Ok(Token::new(Loc::new(6, 4), Ident(Problem))))
+            Some(Ok(Token::new(
+                Loc::new(6, 4),
+                Span::new(111, 112),
+                Ident(Problem)
+            )))
         );
         assert_eq!(
             lexer.next(),
-            Some(Ok(Token::new(Loc::new(6, 6), Ident(Cnf))))
+            Some(Ok(Token::new(
+                Loc::new(6, 6),
+                Span::new(113, 116),
+                Ident(Cnf)
+            )))
         );
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(6, 10), Nat(42)))));
         assert_eq!(
             lexer.next(),
-            Some(Ok(Token::new(Loc::new(6, 13), Nat(1337))))
+            Some(Ok(Token::new(
+                Loc::new(6, 10),
+                Span::new(117, 119),
+                Nat(42)
+            )))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(
+                Loc::new(6, 13),
+                Span::new(120, 124),
+                Nat(1337)
+            )))
         );
 
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(7, 4), Nat(1)))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(7, 6), Nat(2)))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(7, 8), Zero))));
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(7, 4), Span::new(128, 129), Nat(1))))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(7, 6), Span::new(130, 131), Nat(2))))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(7, 8), Span::new(132, 133), Zero)))
+        );
 
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(8, 4), Minus))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(8, 5), Nat(3)))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(8, 7), Nat(4)))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(8, 9), Zero))));
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(8, 4), Span::new(137, 138), Minus)))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(8, 5), Span::new(138, 139), Nat(3))))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(8, 7), Span::new(140, 141), Nat(4))))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(8, 9), Span::new(142, 143), Zero)))
+        );
 
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(9, 4), Nat(5)))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(9, 6), Minus))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(9, 7), Nat(6)))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(9, 9), Nat(7)))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(9, 11), Zero))));
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(9, 4), Span::new(147, 148), Nat(5))))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(9, 6), Span::new(149, 150), Minus)))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(9, 7), Span::new(150, 151), Nat(6))))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(9, 9), Span::new(152, 153), Nat(7))))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(9, 11), Span::new(154, 155), Zero)))
+        );
 
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(10, 4), Minus))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(10, 5), Nat(7)))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(10, 7), Minus))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(10, 8), Nat(8)))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(10, 10), Minus))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(10, 11), Nat(9)))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(10, 13), Zero))));
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(10, 4), Span::new(159, 160), Minus)))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(10, 5), Span::new(160, 161), Nat(7))))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(10, 7), Span::new(162, 163), Minus)))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(10, 8), Span::new(163, 164), Nat(8))))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(10, 10), Span::new(165, 166), Minus)))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(
+                Loc::new(10, 11),
+                Span::new(166, 167),
+                Nat(9)
+            )))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(10, 13), Span::new(168, 169), Zero)))
+        );
 
         assert_eq!(lexer.next(), None);
     }
@@ -383,47 +713,128 @@ mod tests {
 			(*(+(1 3 -4)
 			+(4)
 			+(2 3)))";
-        let mut lexer = Lexer::from(sample.bytes());
+        let mut lexer = Lexer::from(sample.bytes().map(Ok));
 
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(2, 4), Comment))));
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(2, 4), Span::new(4, 29), Comment)))
+        );
 
         assert_eq!(
             lexer.next(),
-            Some(Ok(Token::new(Loc::new(3, 4), Ident(Problem))))
+            Some(Ok(Token::new(
+                Loc::new(3, 4),
+                Span::new(33, 34),
+                Ident(Problem)
+            )))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(
+                Loc::new(3, 6),
+                Span::new(35, 38),
+                Ident(Sat)
+            )))
         );
         assert_eq!(
             lexer.next(),
-            Some(Ok(Token::new(Loc::new(3, 6), Ident(Sat))))
+            Some(Ok(Token::new(Loc::new(3, 10), Span::new(39, 41), Nat(42))))
         );
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(3, 10), Nat(42)))));
         assert_eq!(
             lexer.next(),
-            Some(Ok(Token::new(Loc::new(3, 13), Nat(1337))))
+            Some(Ok(Token::new(
+                Loc::new(3, 13),
+                Span::new(42, 46),
+                Nat(1337)
+            )))
         );
 
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(4, 4), Open))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(4, 5), Star))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(4, 6), Open))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(4, 7), Plus))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(4, 8), Open))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(4, 9), Nat(1)))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(4, 11), Nat(3)))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(4, 13), Minus))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(4, 14), Nat(4)))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(4, 15), Close))));
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(4, 4), Span::new(50, 51), Open)))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(4, 5), Span::new(51, 52), Star)))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(4, 6), Span::new(52, 53), Open)))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(4, 7), Span::new(53, 54), Plus)))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(4, 8), Span::new(54, 55), Open)))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(4, 9), Span::new(55, 56), Nat(1))))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(4, 11), Span::new(57, 58), Nat(3))))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(4, 13), Span::new(59, 60), Minus)))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(4, 14), Span::new(60, 61), Nat(4))))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(4, 15), Span::new(61, 62), Close)))
+        );
 
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(5, 4), Plus))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(5, 5), Open))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(5, 6), Nat(4)))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(5, 7), Close))));
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(5, 4), Span::new(66, 67), Plus)))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(5, 5), Span::new(67, 68), Open)))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(5, 6), Span::new(68, 69), Nat(4))))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(5, 7), Span::new(69, 70), Close)))
+        );
 
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(6, 4), Plus))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(6, 5), Open))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(6, 6), Nat(2)))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(6, 8), Nat(3)))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(6, 9), Close))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(6, 10), Close))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(6, 11), Close))));
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(6, 4), Span::new(74, 75), Plus)))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(6, 5), Span::new(75, 76), Open)))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(6, 6), Span::new(76, 77), Nat(2))))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(6, 8), Span::new(78, 79), Nat(3))))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(6, 9), Span::new(79, 80), Close)))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(6, 10), Span::new(80, 81), Close)))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(6, 11), Span::new(81, 82), Close)))
+        );
 
         assert_eq!(lexer.next(), None);
     }
@@ -431,13 +842,28 @@ mod tests {
     #[test]
     fn tricky_1() {
         let sample = r"(1-2)";
-        let mut lexer = Lexer::from(sample.bytes());
+        let mut lexer = Lexer::from(sample.bytes().map(Ok));
 
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(1, 1), Open))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(1, 2), Nat(1)))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(1, 3), Minus))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(1, 4), Nat(2)))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(1, 5), Close))));
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(1, 1), Span::new(0, 1), Open)))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(1, 2), Span::new(1, 2), Nat(1))))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(1, 3), Span::new(2, 3), Minus)))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(1, 4), Span::new(3, 4), Nat(2))))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(1, 5), Span::new(4, 5), Close)))
+        );
 
         assert_eq!(lexer.next(), None);
     }
@@ -445,35 +871,124 @@ mod tests {
     #[test]
     fn all_idents() {
         let sample = r"p cnf sat satx sate satex xor";
-        let mut lexer = Lexer::from(sample.bytes());
+        let mut lexer = Lexer::from(sample.bytes().map(Ok));
 
         assert_eq!(
             lexer.next(),
-            Some(Ok(Token::new(Loc::new(1, 1), Ident(Problem))))
+            Some(Ok(Token::new(
+                Loc::new(1, 1),
+                Span::new(0, 1),
+                Ident(Problem)
+            )))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(1, 3), Span::new(2, 5), Ident(Cnf))))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(1, 7), Span::new(6, 9), Ident(Sat))))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(
+                Loc::new(1, 11),
+                Span::new(10, 14),
+                Ident(Satx)
+            )))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(
+                Loc::new(1, 16),
+                Span::new(15, 19),
+                Ident(Sate)
+            )))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(
+                Loc::new(1, 21),
+                Span::new(20, 25),
+                Ident(Satex)
+            )))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(
+                Loc::new(1, 27),
+                Span::new(26, 29),
+                Ident(Xor)
+            )))
+        );
+
+        assert_eq!(lexer.next(), None);
+    }
+
+    #[test]
+    fn keywords_include_digits() {
+        let sample = r"x1 xor3";
+        let mut lexer = Lexer::from(sample.bytes().map(Ok));
+
+        assert_eq!(
+            lexer.next(),
+            Some(Err(
+                ParseError::new(Loc::new(1, 1), UnknownKeyword).with_span(Span::new(0, 2))
+            ))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Err(
+                ParseError::new(Loc::new(1, 4), UnknownKeyword).with_span(Span::new(3, 7))
+            ))
+        );
+
+        assert_eq!(lexer.next(), None);
+    }
+
+    #[test]
+    fn xor_lines() {
+        let sample = "x1 x2\nx-3\n c\n x 0";
+        let mut lexer = Lexer::from(sample.bytes().map(Ok));
+        lexer.lex_xor_lines();
+
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(1, 1), Span::new(0, 1), Ident(X))))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(1, 2), Span::new(1, 2), Nat(1))))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Err(
+                ParseError::new(Loc::new(1, 4), UnknownKeyword).with_span(Span::new(3, 5))
+            ))
         );
         assert_eq!(
             lexer.next(),
-            Some(Ok(Token::new(Loc::new(1, 3), Ident(Cnf))))
+            Some(Ok(Token::new(Loc::new(2, 1), Span::new(6, 7), Ident(X))))
         );
         assert_eq!(
             lexer.next(),
-            Some(Ok(Token::new(Loc::new(1, 7), Ident(Sat))))
+            Some(Ok(Token::new(Loc::new(2, 2), Span::new(7, 8), Minus)))
         );
         assert_eq!(
             lexer.next(),
-            Some(Ok(Token::new(Loc::new(1, 11), Ident(Satx))))
+            Some(Ok(Token::new(Loc::new(2, 3), Span::new(8, 9), Nat(3))))
         );
         assert_eq!(
             lexer.next(),
-            Some(Ok(Token::new(Loc::new(1, 16), Ident(Sate))))
+            Some(Ok(Token::new(Loc::new(3, 2), Span::new(11, 12), Comment)))
         );
         assert_eq!(
             lexer.next(),
-            Some(Ok(Token::new(Loc::new(1, 21), Ident(Satex))))
+            Some(Ok(Token::new(Loc::new(4, 2), Span::new(14, 15), Ident(X))))
         );
         assert_eq!(
             lexer.next(),
-            Some(Ok(Token::new(Loc::new(1, 27), Ident(Xor))))
+            Some(Ok(Token::new(Loc::new(4, 4), Span::new(16, 17), Zero)))
         );
 
         assert_eq!(lexer.next(), None);
@@ -482,14 +997,32 @@ mod tests {
     #[test]
     fn all_ops() {
         let sample = r"()+-*=";
-        let mut lexer = Lexer::from(sample.bytes());
+        let mut lexer = Lexer::from(sample.bytes().map(Ok));
 
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(1, 1), Open))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(1, 2), Close))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(1, 3), Plus))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(1, 4), Minus))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(1, 5), Star))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(1, 6), Eq))));
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(1, 1), Span::new(0, 1), Open)))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(1, 2), Span::new(1, 2), Close)))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(1, 3), Span::new(2, 3), Plus)))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(1, 4), Span::new(3, 4), Minus)))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(1, 5), Span::new(4, 5), Star)))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(1, 6), Span::new(5, 6), Eq)))
+        );
 
         assert_eq!(lexer.next(), None);
     }
@@ -497,19 +1030,25 @@ mod tests {
     #[test]
     fn invalid_token_start() {
         let sample = r"# foo Big";
-        let mut lexer = Lexer::from(sample.bytes());
+        let mut lexer = Lexer::from(sample.bytes().map(Ok));
 
         assert_eq!(
             lexer.next(),
-            Some(Err(ParseError::new(Loc::new(1, 1), InvalidTokenStart)))
+            Some(Err(
+                ParseError::new(Loc::new(1, 1), InvalidTokenStart).with_span(Span::new(0, 1))
+            ))
         );
         assert_eq!(
             lexer.next(),
-            Some(Err(ParseError::new(Loc::new(1, 3), UnknownKeyword)))
+            Some(Err(
+                ParseError::new(Loc::new(1, 3), UnknownKeyword).with_span(Span::new(2, 5))
+            ))
         );
         assert_eq!(
             lexer.next(),
-            Some(Err(ParseError::new(Loc::new(1, 7), UnknownKeyword)))
+            Some(Err(
+                ParseError::new(Loc::new(1, 7), UnknownKeyword).with_span(Span::new(6, 9))
+            ))
         );
 
         assert_eq!(lexer.next(), None);
@@ -524,14 +1063,32 @@ mod tests {
 			c But not the following ...
 			c Filter this, too!
 			c And this!";
-        let mut lexer = Lexer::from(sample.bytes());
+        let mut lexer = Lexer::from(sample.bytes().map(Ok));
 
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(2, 4), Comment))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(3, 4), Comment))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(4, 4), Comment))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(5, 4), Comment))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(6, 4), Comment))));
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(7, 4), Comment))));
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(2, 4), Span::new(4, 24), Comment)))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(3, 4), Span::new(28, 45), Comment)))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(4, 4), Span::new(49, 75), Comment)))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(5, 4), Span::new(79, 106), Comment)))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(6, 4), Span::new(110, 129), Comment)))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(7, 4), Span::new(133, 144), Comment)))
+        );
 
         assert_eq!(lexer.next(), None);
     }
@@ -548,12 +1105,101 @@ mod tests {
 			INVALID
 			c And this!
 		";
-        let mut lexer = ValidLexer::from(sample.bytes());
+        let mut lexer = ValidLexer::from(sample.bytes().map(Ok));
+
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(6, 4), Span::new(110, 112), Nat(42))))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Err(
+                ParseError::new(Loc::new(8, 4), UnknownKeyword).with_span(Span::new(139, 146))
+            ))
+        );
+
+        assert_eq!(lexer.next(), None);
+    }
+
+    #[test]
+    fn io_error() {
+        let input = vec![
+            Ok(b'4'),
+            Ok(b'2'),
+            Ok(b'\n'),
+            Ok(b'1'),
+            Err(io::Error::new(io::ErrorKind::UnexpectedEof, "truncated")),
+            Ok(b'3'),
+        ];
+        let mut lexer = Lexer::from(input.into_iter());
+
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(1, 1), Span::new(0, 2), Nat(42))))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Err(ParseError::new(
+                Loc::new(2, 2),
+                Io(io::Error::from(io::ErrorKind::UnexpectedEof).into())
+            )
+            .with_span(Span::new(4, 4))))
+        );
+
+        assert_eq!(lexer.next(), None);
+    }
+
+    #[test]
+    fn nat_overflow() {
+        let sample = r"18446744073709551615 18446744073709551616 99999999999999999999999 1";
+        let mut lexer = Lexer::from(sample.bytes().map(Ok));
 
-        assert_eq!(lexer.next(), Some(Ok(Token::new(Loc::new(6, 4), Nat(42)))));
         assert_eq!(
             lexer.next(),
-            Some(Err(ParseError::new(Loc::new(8, 4), UnknownKeyword)))
+            Some(Ok(Token::new(
+                Loc::new(1, 1),
+                Span::new(0, 20),
+                Nat(u64::MAX)
+            )))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Err(
+                ParseError::new(Loc::new(1, 22), NatOverflow).with_span(Span::new(21, 41))
+            ))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Err(
+                ParseError::new(Loc::new(1, 43), NatOverflow).with_span(Span::new(42, 65))
+            ))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(Loc::new(1, 67), Span::new(66, 67), Nat(1))))
+        );
+
+        assert_eq!(lexer.next(), None);
+    }
+
+    #[test]
+    fn long_keywords() {
+        let sample = r"UNSATISFIABLE UNSATISFIABLEX";
+        let mut lexer = Lexer::from(sample.bytes().map(Ok));
+
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(
+                Loc::new(1, 1),
+                Span::new(0, 13),
+                Ident(Unsatisfiable)
+            )))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Err(
+                ParseError::new(Loc::new(1, 15), UnknownKeyword).with_span(Span::new(14, 28))
+            ))
         );
 
         assert_eq!(lexer.next(), None);
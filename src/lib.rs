@@ -2,31 +2,108 @@
 //! [DIMACS format specification](http://www.domagoj-babic.com/uploads/ResearchProjects/Spear/dimacs-cnf.pdf).
 //!
 //! The DIMACS format was specified for the DIMACS SAT solver competitions as input file format.
-//! Many other DIMACS file formats exist for other competitions, however, this crate currently only
-//! supports the formats that are relevant for SAT solvers.
+//! Many other DIMACS file formats exist for other competitions of which this crate additionally
+//! supports the graph formats `p edge` and `p col` as well as the network formats `p sp`, `p max`
+//! and `p min`.
 //!
 //! In `.cnf` the entire SAT formula is encoded as a conjunction of disjunctions and so mainly stores
 //! a list of clauses consisting of literals.
 //!
 //! The `.sat` format is slightly more difficult as the formula can be of a different shape and thus
 //! a `.sat` file internally looks similar to a Lisp file.
+//!
+//! Clauses of `.cnf` files may be mixed with XOR clauses of `x` lines as used by CryptoMiniSat,
+//! e.g. `x1 -2 3 0`, which `Instance::expand_xors` expands into plain clauses.
+//!
+//! The weighted `.wcnf` format of the MaxSAT Evaluations is supported as well, both with
+//! a `p wcnf` problem line and in the header-less format introduced in 2022 by `parse_wcnf`.
+//!
+//! Quantified boolean formulas are supported in the `.qdimacs` format which extends `.cnf`
+//! by a prefix of existentially (`e`) and universally (`a`) quantified blocks of variables.
+//! Incremental `.icnf` files with a `p inccnf` problem line interleave clauses with `a` lines
+//! of assumptions under which the clauses added so far are solved.
+//!
+//! The `read_*` functions parse any `io::Read` byte source through a buffer just like their
+//! `parse_*` counterparts. Failures of the byte source are reported as `ErrorKind::Io`
+//! errors at the location where the input ended.
+//!
+//! Parsed or hand-built instances can be written back into their DIMACS representation.
+//! Fallible constructors such as `Lit::try_from_i64` and `Instance::try_cnf` validate
+//! hand-built items.
+//! Literals support negation with `!`, are ordered by variable and parse from and display as
+//! their DIMACS form, e.g. `"-42".parse::<Lit>()`.
+//!
+//! Formulas of `.sat` instances can be transformed into equisatisfiable clauses using
+//! either the Tseitin or the Plaisted–Greenbaum encoding.
+//!
+//! Clauses, formulas and instances can be evaluated under a partial `Assignment`,
+//! for example to validate models reported by SAT solvers whose output in the format of the
+//! SAT competitions is parsed and written as well.
+//!
+//! Proofs of unsatisfiability in the DRAT and LRAT formats are read and written
+//! step by step in their textual and binary forms. DRAT proofs of `.cnf` instances can be
+//! verified by the built-in checker yielding an unsatisfiable core and a trimmed LRAT proof.
+//!
+//! Files compressed with gzip, xz, bzip2 or zstd are read by `read_dimacs_path` when the
+//! cargo features `gzip`, `xz`, `bzip2` and `zstd` respectively are enabled.
+//! Very large `.cnf` files are parsed on multiple threads by the `unsafe` `parse_cnf_parallel`
+//! when the cargo feature `parallel` is enabled, which requires that the memory-mapped file
+//! is not modified concurrently, or into
+//! a `ClauseDb` storing all literals in one contiguous buffer, optionally as compact `Lit32`
+//! literals encoded as `2 * index + sign` like within SAT solvers.
 
-#![cfg_attr(all(feature = "bench", test), feature(test))]
+#![cfg_attr(all(feature = "bench", nightly, test), feature(test))]
 #![deny(missing_docs)]
 
-#[cfg(all(feature = "bench", test))]
+#[cfg(all(feature = "bench", nightly, test))]
 extern crate test;
 
 #[macro_use]
 extern crate bitflags;
 
+#[cfg(all(feature = "bench", nightly, test))]
+mod benches;
+mod checker;
+mod compression;
+mod db;
 mod errors;
+mod eval;
 mod items;
 mod lexer;
+mod lit32;
+#[cfg(feature = "parallel")]
+mod parallel;
 mod parser;
+mod proof;
+mod scanner;
+mod transform;
+mod writer;
 
-pub use crate::errors::{ErrorKind, Loc, ParseError, Result};
+pub use crate::checker::{check_drat, CheckError, CheckedProof};
+pub use crate::db::{ClauseDb, ClauseDbIter, ClauseRef};
+pub use crate::errors::{
+    ErrorKind, Expected, IoError, Loc, ParseError, Result, Span, ValidationError,
+};
+pub use crate::eval::Falsified;
 pub use crate::items::{
-    Clause, Extensions, Formula, FormulaBox, FormulaList, Instance, Lit, Sign, Var,
+    Assignment, CapacityArc, Clause, CostArc, Edge, Extensions, Formula, FormulaBox, FormulaList,
+    Graph, GraphKind, IncrementalInstance, IncrementalStep, Instance, Lit, LratStep, Network,
+    NodeSupply, ParseLitError, ProofStep, QdimacsInstance, Quantifier, QuantifierBlock, Sign,
+    SolverOutput, Status, Var, VertexWeight, Weight, WeightedArc, WeightedClause, XorClause,
+};
+pub use crate::lexer::{Ident, TokenKind};
+pub use crate::lit32::{Lit32, TryFromLitError, Var32};
+#[cfg(feature = "parallel")]
+pub use crate::parallel::parse_cnf_parallel;
+pub use crate::parser::{
+    parse_clause_db, parse_dimacs, parse_dimacs_bytes, parse_graph, parse_icnf, parse_network,
+    parse_qdimacs, parse_safe_dimacs, parse_solver_output, parse_wcnf, read_clause_db, read_dimacs,
+    read_dimacs_path, read_graph, read_icnf, read_network, read_qdimacs, read_safe_dimacs,
+    read_solver_output, read_wcnf, CnfReader, IcnfReader,
+};
+pub use crate::proof::{DratReader, DratWriter, LratReader, LratWriter};
+pub use crate::transform::{AuxVars, Definition, Encoding, Gate, TransformError};
+pub use crate::writer::{
+    write_dimacs, write_dimacs_with_comments, write_graph, write_icnf, write_network,
+    write_qdimacs, write_solver_output,
 };
-pub use crate::parser::{parse_dimacs, read_dimacs};
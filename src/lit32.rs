@@ -0,0 +1,182 @@
+//! Compact 32-bit variables and literals as used within SAT solvers.
+//!
+//! Variables are zero-based indices, i.e. the DIMACS variable `1` has the index `0`,
+//! and literals are encoded as `2 * index + sign` where the sign bit is set for negative
+//! literals. Both can be used directly to index arrays such as watch lists.
+
+use std::convert::TryFrom;
+use std::error;
+use std::fmt;
+use std::ops::Not;
+
+use crate::items::*;
+
+/// Represents a variable by its zero-based index in 31 bits.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Var32(u32);
+
+impl Var32 {
+    /// The largest index of a `Var32`.
+    pub const MAX_INDEX: u32 = u32::MAX >> 1;
+
+    /// Creates a variable from its zero-based index.
+    ///
+    /// # Panics
+    ///
+    /// If the index is greater than `Var32::MAX_INDEX`.
+    pub fn from_index(index: u32) -> Var32 {
+        assert!(index <= Var32::MAX_INDEX, "variable index out of range");
+        Var32(index)
+    }
+
+    /// Returns the zero-based index of this variable.
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    /// Returns the literal of this variable with the given sign.
+    pub fn lit(self, sign: Sign) -> Lit32 {
+        Lit32::new(self, sign)
+    }
+}
+
+/// Represents a literal as `2 * index + sign` of its variable in 32 bits.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Lit32(u32);
+
+impl Lit32 {
+    /// Creates the literal of the given variable with the given sign.
+    pub fn new(var: Var32, sign: Sign) -> Lit32 {
+        Lit32(var.0 << 1 | (sign == Sign::Neg) as u32)
+    }
+
+    /// Creates a literal from its encoding as returned by `Lit32::index`.
+    pub fn from_index(index: u32) -> Lit32 {
+        Lit32(index)
+    }
+
+    /// Returns the encoding `2 * index + sign` of this literal.
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    /// Returns the variable of this literal.
+    pub fn var(self) -> Var32 {
+        Var32(self.0 >> 1)
+    }
+
+    /// Returns the sign of this literal.
+    pub fn sign(self) -> Sign {
+        if self.0 & 1 == 0 {
+            Sign::Pos
+        } else {
+            Sign::Neg
+        }
+    }
+}
+
+impl Not for Lit32 {
+    type Output = Lit32;
+
+    fn not(self) -> Lit32 {
+        Lit32(self.0 ^ 1)
+    }
+}
+
+/// The error returned when a variable or literal is not representable in 32 bits.
+///
+/// This is the case for the invalid DIMACS variable `0` as well as for
+/// variables greater than `Var32::MAX_INDEX + 1` when converting into `Lit32`,
+/// and for literals outside of the range of `i32` when converting into `i32`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TryFromLitError(pub(crate) ());
+
+impl fmt::Display for TryFromLitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("variable out of range for 32-bit literals")
+    }
+}
+
+impl error::Error for TryFromLitError {}
+
+impl TryFrom<Var> for Var32 {
+    type Error = TryFromLitError;
+
+    fn try_from(var: Var) -> Result<Var32, TryFromLitError> {
+        match var.to_u64().checked_sub(1) {
+            Some(index) if index <= Var32::MAX_INDEX as u64 => Ok(Var32(index as u32)),
+            _ => Err(TryFromLitError(())),
+        }
+    }
+}
+
+impl From<Var32> for Var {
+    fn from(var: Var32) -> Var {
+        Var(var.0 as u64 + 1)
+    }
+}
+
+impl TryFrom<Lit> for Lit32 {
+    type Error = TryFromLitError;
+
+    fn try_from(lit: Lit) -> Result<Lit32, TryFromLitError> {
+        Ok(Lit32::new(Var32::try_from(lit.var())?, lit.sign()))
+    }
+}
+
+impl From<Lit32> for Lit {
+    fn from(lit: Lit32) -> Lit {
+        Lit::new(Var::from(lit.var()), lit.sign())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::db::ClauseDb;
+    use crate::errors::{ErrorKind, Loc, ParseError, Span};
+    use crate::parser::parse_clause_db;
+
+    fn lit32(val: i64) -> Lit32 {
+        Lit32::try_from(Lit::from_i64(val)).expect("representable literal")
+    }
+
+    #[test]
+    fn encoding() {
+        assert_eq!(lit32(1).index(), 0);
+        assert_eq!(lit32(-1).index(), 1);
+        assert_eq!(lit32(-3).index(), 5);
+        assert_eq!(lit32(-3).var(), Var32::from_index(2));
+        assert_eq!(lit32(-3).sign(), Sign::Neg);
+        assert_eq!(!lit32(-3), lit32(3));
+        assert_eq!(Var32::from_index(2).lit(Sign::Pos), lit32(3));
+        assert_eq!(Lit32::from_index(5), lit32(-3));
+    }
+
+    #[test]
+    fn conversions() {
+        for &val in &[1, -1, 42, -42, 2147483648, -2147483648] {
+            assert_eq!(Lit::from(lit32(val)), Lit::from_i64(val));
+        }
+        for &val in &[0, 2147483649, -2147483649, i64::MAX] {
+            assert_eq!(
+                Lit32::try_from(Lit::from_i64(val)),
+                Err(TryFromLitError(()))
+            );
+        }
+        assert_eq!(Var::from(Var32::from_index(0)), Var(1));
+        assert_eq!(Var32::try_from(Var(0)), Err(TryFromLitError(())));
+    }
+
+    #[test]
+    fn parse_lit32() {
+        let db: ClauseDb<Lit32> = parse_clause_db(b"p cnf 3 2\n1 -2 0\n3 0\n").expect("valid .cnf");
+        assert_eq!(db.lits(), &[lit32(1), lit32(-2), lit32(3)]);
+        assert_eq!(
+            parse_clause_db::<Lit32>(b"p cnf 3 2\n1 -2 0\n-2147483649 0\n"),
+            Err(ParseError::new(Loc::new(3, 1), ErrorKind::LitOutOfRange)
+                .with_span(Span::new(17, 28)))
+        );
+    }
+}
@@ -0,0 +1,196 @@
+//! Parsing of large `.cnf` files on multiple threads.
+//!
+//! The file is memory-mapped and its clause section is split into chunks that end
+//! with lines terminating a clause. Every chunk is scanned on its own thread and the
+//! clauses and XOR clauses of all chunks are concatenated in order afterwards.
+
+use std::fs::File;
+use std::path::Path;
+use std::thread;
+
+use memmap2::Mmap;
+
+use crate::errors::*;
+use crate::items::*;
+use crate::parser::{parse_dimacs_bytes, Parser};
+use crate::scanner::{ends_clause, ClauseVec, Scanner};
+
+/// Returns the location just before the byte at the given offset as tracked by the `Lexer`.
+fn loc_at(input: &[u8], offset: usize) -> Loc {
+    let line = 1 + memchr::memchr_iter(b'\n', &input[..offset]).count() as u64;
+    let line_start = memchr::memrchr(b'\n', &input[..offset]).map_or(0, |pos| pos + 1);
+    Loc::new(line, (offset - line_start) as u64)
+}
+
+/// Returns the offset after the first line at or after `from` that terminates a clause.
+///
+/// The line containing `from` is skipped since `from` might be within a comment.
+fn find_boundary(input: &[u8], from: usize) -> Option<usize> {
+    let mut line_start = from + memchr::memchr(b'\n', &input[from..])? + 1;
+    loop {
+        let line_end = line_start + memchr::memchr(b'\n', &input[line_start..])?;
+        if ends_clause(&input[line_start..line_end]) {
+            return Some(line_end + 1);
+        }
+        line_start = line_end + 1;
+    }
+}
+
+/// Splits the clause section starting at `start` into at most `chunks` chunks
+/// and returns the offsets delimiting them.
+fn split(input: &[u8], start: usize, chunks: usize) -> Vec<usize> {
+    let chunk_len = (input.len() - start) / chunks;
+    let mut bounds = vec![start];
+    for chunk in 1..chunks {
+        let from = (start + chunk * chunk_len).max(bounds[bounds.len() - 1]);
+        match find_boundary(input, from) {
+            Some(bound) if bound < input.len() => bounds.push(bound),
+            _ => break,
+        }
+    }
+    bounds.push(input.len());
+    bounds
+}
+
+/// Parses the clauses and XOR clauses between the given offsets.
+///
+/// Returns `None` if the chunk contains a `\0` byte which the `Lexer` treats as end of file.
+fn parse_chunk(
+    input: &[u8],
+    from: usize,
+    to: usize,
+) -> Option<Result<(Vec<Clause>, Vec<XorClause>)>> {
+    let chunk = &input[from..to];
+    if memchr::memchr(b'\0', chunk).is_some() {
+        return None;
+    }
+    let mut clauses = ClauseVec::default();
+    if Scanner::new(chunk).scan_clauses(&mut clauses).is_some() {
+        return Some(Ok((clauses.clauses, Vec::new())));
+    }
+    let bytes = chunk.iter().cloned().map(Ok);
+    let mut parser = Parser::starting_at(bytes, loc_at(input, from), from as u64);
+    parser.lex_xor_lines();
+    Some(parser.consume().and_then(|_| parser.parse_cnf_clauses(0)))
+}
+
+fn parse_cnf_chunks(input: &[u8], threads: usize) -> Result<Instance> {
+    let mut scanner = Scanner::new(input);
+    let num_vars = match scanner.scan_header() {
+        Some((num_vars, _)) => num_vars,
+        None => return parse_dimacs_bytes(input),
+    };
+    let bounds = split(input, scanner.pos(), threads.max(1));
+    let chunks: Vec<_> = thread::scope(|scope| {
+        let handles: Vec<_> = bounds
+            .windows(2)
+            .map(|bounds| scope.spawn(move || parse_chunk(input, bounds[0], bounds[1])))
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("parser thread panicked"))
+            .collect()
+    });
+    let mut clauses = Vec::new();
+    let mut xors = Vec::new();
+    for chunk in chunks {
+        match chunk {
+            Some(chunk) => {
+                let (chunk_clauses, chunk_xors) = chunk?;
+                clauses.extend(chunk_clauses);
+                xors.extend(chunk_xors);
+            }
+            None => return parse_dimacs_bytes(input),
+        }
+    }
+    Ok(Instance::cnf_with_xors(num_vars, clauses, xors))
+}
+
+/// Parses the `.cnf` file at the given path using the given number of threads.
+///
+/// The file is memory-mapped and its clauses are parsed in parallel while errors are
+/// reported with the same locations as by `read_dimacs`. Files that are no `.cnf` files
+/// are parsed sequentially just like `read_dimacs_path` without decompression.
+///
+/// # Safety
+///
+/// The file must not be modified, truncated or removed by this or any other process
+/// while it is parsed. Since the file is memory-mapped, such modifications are undefined
+/// behavior. Use `read_dimacs_path` for files that may change concurrently.
+pub unsafe fn parse_cnf_parallel<P: AsRef<Path>>(path: P, threads: usize) -> Result<Instance> {
+    let file = File::open(path).map_err(ParseError::io_at_start)?;
+    if file.metadata().map_err(ParseError::io_at_start)?.len() == 0 {
+        return parse_dimacs_bytes(&[]);
+    }
+    // SAFETY: the caller guarantees that the file is not modified while it is mapped.
+    let input = unsafe { Mmap::map(&file) }.map_err(ParseError::io_at_start)?;
+    parse_cnf_chunks(&input, threads)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io;
+
+    use crate::parser::read_dimacs_path;
+
+    #[test]
+    fn parallel_bench_files() {
+        for file in &["bench/bf0432-007.cnf", "bench/zebra-v155-c1135.cnf"] {
+            let expected = read_dimacs_path(file).expect("valid .cnf");
+            for threads in 0..5 {
+                // SAFETY: the benchmark files are not modified by any test.
+                let parsed = unsafe { parse_cnf_parallel(file, threads) };
+                assert_eq!(parsed, Ok(expected.clone()));
+            }
+        }
+    }
+
+    #[test]
+    fn missing_file() {
+        let path = "bench/missing.cnf";
+        // SAFETY: the file does not exist.
+        let parsed = unsafe { parse_cnf_parallel(path, 2) };
+        assert_eq!(
+            parsed,
+            Err(ParseError::new(
+                Loc::new(1, 1),
+                ErrorKind::Io(io::Error::from(io::ErrorKind::NotFound).into())
+            ))
+        );
+        assert_eq!(parsed, read_dimacs_path(path));
+    }
+
+    #[test]
+    fn split_at_clause_ends() {
+        let input = b"p cnf 3 4\n1 2\n-3 0\nc 0\n2 0 3\n1 0\n-1 0\n";
+        let start = 9;
+        assert_eq!(split(input, start, 1), vec![start, input.len()]);
+        assert_eq!(split(input, start, 2), vec![start, 33, input.len()]);
+        assert_eq!(split(input, start, 8), vec![start, 19, 33, input.len()]);
+        assert_eq!(parse_cnf_chunks(input, 8), parse_dimacs_bytes(input),);
+    }
+
+    #[test]
+    fn parallel_errors() {
+        let valid = "p cnf 3 5\n1 2 0\n-3 0\n- 2 0\n3 1 0\n-1 0\n";
+        assert_eq!(
+            parse_cnf_chunks(valid.as_bytes(), 3),
+            parse_dimacs_bytes(valid.as_bytes())
+        );
+        let invalid = "p cnf 3 5\n1 2 0\n-3 0\n2 0\n3 1 0\n-1 x 0\n";
+        let expected = parse_dimacs_bytes(invalid.as_bytes());
+        assert!(expected.is_err());
+        assert_eq!(parse_cnf_chunks(invalid.as_bytes(), 3), expected);
+        let xors = "p cnf 3 5\n1 2 0\nx-3 1 0\n2 0\nx3 1 0\n-1 0\n";
+        let expected = parse_dimacs_bytes(xors.as_bytes());
+        assert!(matches!(expected, Ok(Instance::Cnf { ref xors, .. }) if xors.len() == 2));
+        assert_eq!(parse_cnf_chunks(xors.as_bytes(), 3), expected);
+        let nul = "p cnf 3 5\n1 2 0\n-3 0\n2 0\n3 1 0\n-1\0 x 0\n";
+        assert_eq!(
+            parse_cnf_chunks(nul.as_bytes(), 3),
+            parse_dimacs_bytes(nul.as_bytes())
+        );
+    }
+}
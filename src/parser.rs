@@ -2,8 +2,9 @@
 //! [DIMACS format specification](http://www.domagoj-babic.com/uploads/ResearchProjects/Spear/dimacs-cnf.pdf).
 //!
 //! The DIMACS format was specified for the DIMACS SAT solver competitions as input file format.
-//! Many other DIMACS file formats exist for other competitions, however, this crate currently only
-//! supports the formats that are relevant for SAT solvers.
+//! Many other DIMACS file formats exist for other competitions of which this crate additionally
+//! supports the graph formats `p edge` and `p col` as well as the network formats `p sp`, `p max`
+//! and `p min`.
 //!
 //! In `.cnf` the entire SAT formula is encoded as a conjunction of disjunctions and so mainly stores
 //! a list of clauses consisting of literals.
@@ -11,62 +12,154 @@
 //! The `.sat` format is slightly more difficult as the formula can be of a different shape and thus
 //! a `.sat` file internally looks similar to a Lisp file.
 
-use std::io::Read;
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::io::{self, BufReader, Bytes, Read};
+use std::path::Path;
+
+use crate::compression::open_path;
+use crate::db::ClauseDb;
 
 use crate::errors::*;
 use crate::items::*;
 use crate::lexer::*;
+use crate::scanner::{scan_cnf, ClauseSink, Scanner};
+
+/// The greatest number of items reserved upfront for counts declared in a header.
+const MAX_RESERVED: u64 = 1 << 16;
+
+/// Returns the capacity to reserve for the given number of items declared in a header.
+///
+/// Headers are part of the untrusted input so they must not decide how much memory is
+/// allocated before any of the declared items were actually parsed.
+fn reserved(num_declared: u64) -> usize {
+    num_declared.min(MAX_RESERVED) as usize
+}
+
+/// A line within the clause section of a `.cnf` file.
+enum CnfLine {
+    Clause(Clause),
+    Xor(XorClause),
+}
 
 #[derive(Debug, Clone)]
-struct Parser<I>
+pub(crate) struct Parser<I>
 where
-    I: Iterator<Item = u8>,
+    I: Iterator<Item = io::Result<u8>>,
 {
     tokens: ValidLexer<I>,
     peek: Result<Token>,
+
+    /// whether the enhanced checks of `parse_safe_dimacs` are enabled
+    safe: bool,
+
+    /// the number of variables declared in the problem line
+    num_vars: u64,
+
+    /// the extensions declared in the problem line of a `.sat` file
+    extensions: Extensions,
 }
 
 impl<I> Parser<I>
 where
-    I: Iterator<Item = u8>,
+    I: Iterator<Item = io::Result<u8>>,
 {
-    fn from(input: I) -> Parser<I> {
+    pub(crate) fn from(input: I) -> Parser<I> {
+        Parser::with_tokens(ValidLexer::from(input))
+    }
+
+    /// Creates a parser for input that starts at the given location and byte offset
+    /// of a larger input.
+    #[cfg(feature = "parallel")]
+    pub(crate) fn starting_at(input: I, loc: Loc, offset: u64) -> Parser<I> {
+        Parser::with_tokens(ValidLexer::starting_at(input, loc, offset))
+    }
+
+    fn with_tokens(tokens: ValidLexer<I>) -> Parser<I> {
         Parser {
-            tokens: ValidLexer::from(input),
+            tokens,
             peek: Err(ParseError::new(Loc::new(0, 0), ErrorKind::EmptyTokenStream)),
+            safe: false,
+            num_vars: 0,
+            extensions: Extensions::NONE,
+        }
+    }
+
+    fn safe(input: I) -> Parser<I> {
+        Parser {
+            safe: true,
+            ..Parser::from(input)
         }
     }
 
+    /// Lexes the `x` lines of XOR clauses in the following clauses of a `.cnf` file.
+    pub(crate) fn lex_xor_lines(&mut self) {
+        self.tokens.lex_xor_lines()
+    }
+
+    /// Returns the peeked token or the error that occured while peeking it.
+    fn peek(&self) -> Result<Token> {
+        self.peek.clone()
+    }
+
     fn mk_err(&self, kind: ErrorKind) -> ParseError {
-        ParseError::new(self.peek_loc(), kind)
+        match self.peek {
+            Ok(tok) => ParseError::new(tok.loc, kind)
+                .with_span(tok.span)
+                .with_found(tok.kind),
+            Err(ref err) => ParseError::new(err.loc, kind).with_span(err.span),
+        }
     }
 
     fn err<T>(&self, kind: ErrorKind) -> Result<T> {
         Err(self.mk_err(kind))
     }
 
+    fn err_expected<T>(&self, kind: ErrorKind, expected: Expected) -> Result<T> {
+        Err(self.mk_err(kind).with_expected(expected))
+    }
+
+    /// Reports the peeked token, or the end of file if reached, as unexpected.
+    fn err_unexpected<T>(&self, expected: Expected) -> Result<T> {
+        let kind = if self.is_at_eof() {
+            ErrorKind::UnexpectedEndOfFile
+        } else {
+            ErrorKind::UnexpectedToken
+        };
+        self.err_expected(kind, expected)
+    }
+
     fn peek_loc(&self) -> Loc {
         match self.peek {
             Ok(tok) => tok.loc,
-            Err(err) => err.loc,
+            Err(ref err) => err.loc,
+        }
+    }
+
+    fn peek_span(&self) -> Span {
+        match self.peek {
+            Ok(tok) => tok.span,
+            Err(ref err) => err.span,
         }
     }
 
-    fn consume(&mut self) -> Result<Token> {
-        self.peek = self
-            .tokens
-            .next()
-            .unwrap_or(Ok(Token::new(self.peek_loc(), TokenKind::EndOfFile)));
-        self.peek
+    pub(crate) fn consume(&mut self) -> Result<Token> {
+        let end = self.peek_span().end();
+        self.peek = self.tokens.next().unwrap_or(Ok(Token::new(
+            self.peek_loc(),
+            Span::new(end, end),
+            TokenKind::EndOfFile,
+        )));
+        self.peek()
     }
 
     fn expect(&mut self, expected: TokenKind) -> Result<Token> {
         use self::ErrorKind::{UnexpectedEndOfFile, UnexpectedToken};
         use self::TokenKind::EndOfFile;
-        match self.peek?.kind {
+        match self.peek()?.kind {
             k if k == expected => self.consume(),
-            EndOfFile => self.err(UnexpectedEndOfFile),
-            _ => self.err(UnexpectedToken),
+            EndOfFile => self.err_expected(UnexpectedEndOfFile, expected.into()),
+            _ => self.err_expected(UnexpectedToken, expected.into()),
         }
     }
 
@@ -78,12 +171,12 @@ where
     }
 
     fn expect_nat(&mut self) -> Result<u64> {
-        match self.peek?.kind {
+        match self.peek()?.kind {
             TokenKind::Nat(val) => {
                 self.consume()?;
                 Ok(val)
             }
-            _ => self.err(ErrorKind::ExpectedNat),
+            _ => self.err_expected(ErrorKind::ExpectedNat, Expected::NAT),
         }
     }
 
@@ -91,63 +184,291 @@ where
         use self::Ident::*;
         use self::TokenKind::Ident;
         self.expect(Ident(Problem))?;
-        match self.peek?.kind {
+        match self.peek()?.kind {
             Ident(Cnf) => self.parse_cnf_header(),
             Ident(Sat) | Ident(Sate) | Ident(Satx) | Ident(Satex) => self.parse_sat_header(),
-            _ => self.err(ErrorKind::UnexpectedToken),
+            Ident(Wcnf) => self.parse_wcnf_header(),
+            _ => self.err_expected(
+                ErrorKind::UnexpectedToken,
+                Expected::CNF
+                    | Expected::SAT
+                    | Expected::SATE
+                    | Expected::SATX
+                    | Expected::SATEX
+                    | Expected::WCNF,
+            ),
         }
     }
 
-    fn parse_cnf_header(&mut self) -> Result<Instance> {
+    fn parse_cnf_problem(&mut self) -> Result<(u64, u64)> {
         self.expect(TokenKind::Ident(Ident::Cnf))?;
         let num_vars = self.expect_nat()?;
         let num_clauses = self.expect_nat()?;
-        Ok(Instance::cnf(num_vars, self.parse_clauses(num_clauses)?))
+        self.num_vars = num_vars;
+        Ok((num_vars, num_clauses))
+    }
+
+    fn parse_cnf_header(&mut self) -> Result<Instance> {
+        self.lex_xor_lines();
+        let (num_vars, num_clauses) = self.parse_cnf_problem()?;
+        let (clauses, xors) = self.parse_cnf_clauses(num_clauses)?;
+        Ok(Instance::cnf_with_xors(num_vars, clauses, xors))
+    }
+
+    /// Creates the literal spanning from the `first` to the `last` token with the given
+    /// sign and value, checking that it is representable and within the declared variables.
+    fn mk_lit(&self, first: Token, last: Token, negative: bool, val: u64) -> Result<Lit> {
+        let mk_err = |kind| {
+            ParseError::new(first.loc, kind)
+                .with_span(Span::new(first.span.start(), last.span.end()))
+        };
+        if val > i64::MAX as u64 {
+            return Err(mk_err(ErrorKind::LitOverflow));
+        }
+        if self.safe && val > self.num_vars {
+            return Err(mk_err(ErrorKind::TooManyVariables));
+        }
+        let val = val as i64;
+        Ok(Lit::from_i64(if negative { -val } else { val }))
+    }
+
+    fn check_extension(&self, extension: Extensions, kind: ErrorKind) -> Result<()> {
+        if self.safe && !self.extensions.contains(extension) {
+            return self.err(kind);
+        }
+        Ok(())
     }
 
     fn parse_lit(&mut self) -> Result<Lit> {
-        match self.peek?.kind {
+        self.parse_spanned_lit().map(|(lit, _, _)| lit)
+    }
+
+    /// Parses a literal and returns it together with its location and span.
+    fn parse_spanned_lit(&mut self) -> Result<(Lit, Loc, Span)> {
+        let first = self.peek()?;
+        let negative = match first.kind {
             TokenKind::Minus => {
                 self.consume()?;
-                Ok(Lit::from_i64(-(self.expect_nat()? as i64)))
-            }
-            TokenKind::Nat(val) => {
-                self.consume()?;
-                Ok(Lit::from_i64(val as i64))
+                true
             }
-            _ => self.err(ErrorKind::ExpectedLit),
-        }
+            TokenKind::Nat(_) => false,
+            _ => return self.err_expected(ErrorKind::ExpectedLit, Expected::NAT | Expected::MINUS),
+        };
+        let last = self.peek()?;
+        let val = self.expect_nat()?;
+        let lit = self.mk_lit(first, last, negative, val)?;
+        Ok((
+            lit,
+            first.loc,
+            Span::new(first.span.start(), last.span.end()),
+        ))
     }
 
-    fn parse_clause(&mut self) -> Result<Clause> {
+    /// Parses literals up to and including a terminating `0` or the end of file.
+    fn parse_lits(&mut self) -> Result<Vec<Lit>> {
         use self::ErrorKind::UnexpectedToken;
         use self::TokenKind::{EndOfFile, Minus, Nat, Zero};
         let mut lits = Vec::new();
         loop {
-            match self.peek?.kind {
+            match self.peek()?.kind {
                 Minus | Nat(_) => lits.push(self.parse_lit()?),
                 Zero | EndOfFile => {
                     self.consume()?;
-                    return Ok(Clause::from_vec(lits));
+                    return Ok(lits);
+                }
+                _ => {
+                    return self.err_expected(
+                        UnexpectedToken,
+                        Expected::NAT | Expected::MINUS | Expected::ZERO | Expected::END_OF_FILE,
+                    )
                 }
-                _ => return self.err(UnexpectedToken),
             }
         }
     }
 
-    fn parse_clauses(&mut self, num_clauses: u64) -> Result<Vec<Clause>> {
-        let mut clauses = Vec::with_capacity(num_clauses as usize);
-        while !self.is_at_eof() {
-            clauses.push(self.parse_clause()?);
+    fn parse_clause(&mut self) -> Result<Clause> {
+        self.parse_lits().map(Clause::from_vec)
+    }
+
+    /// Parses the next item with `parse` unless the end of file is reached.
+    ///
+    /// The enhanced checks verify that the number of parsed items matches the declared number
+    /// where `start` denotes the token kinds an item may start with.
+    fn parse_next<T, F>(
+        &mut self,
+        num_parsed: u64,
+        num_declared: u64,
+        start: Expected,
+        parse: F,
+    ) -> Result<Option<T>>
+    where
+        F: FnOnce(&mut Self) -> Result<T>,
+    {
+        if self.is_at_eof() {
+            if self.safe && num_parsed < num_declared {
+                return self.err_expected(ErrorKind::TooFewClauses, start);
+            }
+            return Ok(None);
+        }
+        if self.safe && num_parsed == num_declared {
+            return self.err_expected(ErrorKind::TooManyClauses, Expected::END_OF_FILE);
+        }
+        parse(self).map(Some)
+    }
+
+    fn parse_next_clause(&mut self, num_parsed: u64, num_clauses: u64) -> Result<Option<Clause>> {
+        self.parse_next(
+            num_parsed,
+            num_clauses,
+            Expected::NAT | Expected::MINUS | Expected::ZERO,
+            Self::parse_clause,
+        )
+    }
+
+    /// Parses a clause or an XOR clause starting with the `x` keyword.
+    fn parse_cnf_line(&mut self) -> Result<CnfLine> {
+        if self.peek()?.kind == TokenKind::Ident(Ident::X) {
+            self.consume()?;
+            return Ok(CnfLine::Xor(XorClause::from_vec(self.parse_lits()?)));
+        }
+        self.parse_clause().map(CnfLine::Clause)
+    }
+
+    /// Parses the clauses and XOR clauses of a `.cnf` file.
+    ///
+    /// The declared number of clauses counts both clauses and XOR clauses.
+    pub(crate) fn parse_cnf_clauses(
+        &mut self,
+        num_clauses: u64,
+    ) -> Result<(Vec<Clause>, Vec<XorClause>)> {
+        let mut clauses = Vec::with_capacity(reserved(num_clauses));
+        let mut xors = Vec::new();
+        loop {
+            let num_parsed = (clauses.len() + xors.len()) as u64;
+            let line = self.parse_next(
+                num_parsed,
+                num_clauses,
+                Expected::NAT | Expected::MINUS | Expected::ZERO,
+                Self::parse_cnf_line,
+            )?;
+            match line {
+                Some(CnfLine::Clause(clause)) => clauses.push(clause),
+                Some(CnfLine::Xor(xor)) => xors.push(xor),
+                None => return Ok((clauses, xors)),
+            }
+        }
+    }
+
+    pub(crate) fn parse_clauses(&mut self, num_clauses: u64) -> Result<Vec<Clause>> {
+        let mut clauses = Vec::with_capacity(reserved(num_clauses));
+        while let Some(clause) = self.parse_next_clause(clauses.len() as u64, num_clauses)? {
+            clauses.push(clause);
         }
         Ok(clauses)
     }
 
-    fn parse_sat_extensions<'a>(&'a mut self) -> Result<Extensions> {
+    /// Parses a `.cnf` file into a `ClauseDb` converting every literal into `L`.
+    pub(crate) fn parse_clause_db<L: TryFrom<Lit>>(&mut self) -> Result<ClauseDb<L>> {
+        use self::TokenKind::{EndOfFile, Minus, Nat, Zero};
+        self.consume()?;
+        self.expect(TokenKind::Ident(Ident::Problem))?;
+        let (num_vars, _) = self.parse_cnf_problem()?;
+        let mut db = ClauseDb::new(num_vars);
+        let mut open = false;
+        loop {
+            match self.peek()?.kind {
+                Minus | Nat(_) => {
+                    let (lit, loc, span) = self.parse_spanned_lit()?;
+                    if db.push_lit(lit).is_none() {
+                        return Err(ParseError::new(loc, ErrorKind::LitOutOfRange).with_span(span));
+                    }
+                    open = true;
+                }
+                Zero => {
+                    self.consume()?;
+                    db.end_clause();
+                    open = false;
+                }
+                EndOfFile => {
+                    if open {
+                        db.end_clause();
+                    }
+                    return Ok(db);
+                }
+                _ => {
+                    return self.err_expected(
+                        ErrorKind::UnexpectedToken,
+                        Expected::NAT | Expected::MINUS | Expected::ZERO | Expected::END_OF_FILE,
+                    )
+                }
+            }
+        }
+    }
+
+    fn parse_wcnf_header(&mut self) -> Result<Instance> {
+        self.expect(TokenKind::Ident(Ident::Wcnf))?;
+        // Empty instances are written with zero counts, see `write_dimacs`.
+        let num_vars = self.expect_num()?;
+        let num_clauses = self.expect_num()?;
+        let top = self.expect_nat()?;
+        self.num_vars = num_vars;
+        let mut clauses = Vec::with_capacity(reserved(num_clauses));
+        while let Some(clause) =
+            self.parse_next(clauses.len() as u64, num_clauses, Expected::NAT, |parser| {
+                parser.parse_weighted_clause(top)
+            })?
+        {
+            clauses.push(clause);
+        }
+        Ok(Instance::wcnf(num_vars, clauses))
+    }
+
+    fn parse_weighted_clause(&mut self, top: u64) -> Result<WeightedClause> {
+        let weight = match self.expect_nat()? {
+            weight if weight >= top => Weight::Hard,
+            weight => Weight::Soft(weight),
+        };
+        Ok(WeightedClause::new(weight, self.parse_clause()?))
+    }
+
+    /// Parses the header-less `.wcnf` format introduced by the MaxSAT Evaluation 2022
+    /// where hard clauses are marked with `h` and soft clauses start with their weight.
+    ///
+    /// The number of variables is implied by the greatest variable used in the clauses.
+    fn parse_wcnf_headerless(&mut self) -> Result<Instance> {
+        // There is no declared number of variables that the enhanced checks could verify.
+        self.num_vars = u64::MAX;
+        let mut clauses = Vec::new();
+        let mut num_vars = 0;
+        while !self.is_at_eof() {
+            let weight = match self.peek()?.kind {
+                TokenKind::Ident(Ident::Hard) => {
+                    self.consume()?;
+                    Weight::Hard
+                }
+                TokenKind::Nat(weight) => {
+                    self.consume()?;
+                    Weight::Soft(weight)
+                }
+                _ => {
+                    return self
+                        .err_expected(ErrorKind::UnexpectedToken, Expected::HARD | Expected::NAT)
+                }
+            };
+            let clause = self.parse_clause()?;
+            for lit in clause.lits() {
+                num_vars = num_vars.max(lit.var().to_u64());
+            }
+            clauses.push(WeightedClause::new(weight, clause));
+        }
+        Ok(Instance::wcnf(num_vars, clauses))
+    }
+
+    fn parse_sat_extensions(&mut self) -> Result<Extensions> {
         use self::ErrorKind::*;
         use self::Ident::{Sat, Sate, Satex, Satx};
         use self::TokenKind::Ident;
-        match self.peek?.kind {
+        match self.peek()?.kind {
             Ident(Sat) => {
                 self.consume()?;
                 Ok(Extensions::NONE)
@@ -164,13 +485,18 @@ where
                 self.consume()?;
                 Ok(Extensions::EQ | Extensions::XOR)
             }
-            _ => self.err(InvalidSatExtension),
+            _ => self.err_expected(
+                InvalidSatExtension,
+                Expected::SAT | Expected::SATE | Expected::SATX | Expected::SATEX,
+            ),
         }
     }
 
     fn parse_sat_header(&mut self) -> Result<Instance> {
         let extensions = self.parse_sat_extensions()?;
         let num_vars = self.expect_nat()?;
+        self.num_vars = num_vars;
+        self.extensions = extensions;
         Ok(Instance::sat(
             num_vars,
             extensions,
@@ -181,11 +507,11 @@ where
     fn parse_formula(&mut self) -> Result<Formula> {
         use crate::lexer::Ident::*;
         use crate::lexer::TokenKind::*;
-        let tok = self.peek?;
+        let tok = self.peek()?;
         match tok.kind {
             Nat(val) => {
                 self.consume()?;
-                Ok(Formula::lit(Lit::from_i64(val as i64)))
+                Ok(Formula::lit(self.mk_lit(tok, tok, false, val)?))
             }
             Open => self.parse_paren_formula(),
             Plus => self.parse_or_formula(),
@@ -193,13 +519,22 @@ where
             Minus => self.parse_neg_formula(),
             Eq => self.parse_eq_formula(),
             Ident(Xor) => self.parse_xor_formula(),
-            _ => self.err(ErrorKind::UnexpectedToken),
+            _ => self.err_expected(
+                ErrorKind::UnexpectedToken,
+                Expected::NAT
+                    | Expected::OPEN
+                    | Expected::PLUS
+                    | Expected::STAR
+                    | Expected::MINUS
+                    | Expected::EQ
+                    | Expected::XOR,
+            ),
         }
     }
 
     fn parse_formula_list(&mut self) -> Result<Vec<Formula>> {
         let mut formulas = Vec::new();
-        while self.peek?.kind != TokenKind::Close {
+        while self.peek()?.kind != TokenKind::Close {
             formulas.push(self.parse_formula()?);
         }
         Ok(formulas)
@@ -220,8 +555,9 @@ where
     }
 
     fn parse_neg_formula(&mut self) -> Result<Formula> {
+        let minus = self.peek()?;
         self.expect(TokenKind::Minus)?;
-        let tok = self.peek?;
+        let tok = self.peek()?;
         match tok.kind {
             TokenKind::Open => {
                 self.expect(TokenKind::Open)?;
@@ -231,9 +567,9 @@ where
             }
             TokenKind::Nat(val) => {
                 self.consume()?;
-                Ok(Formula::lit(Lit::from_i64(-(val as i64))))
+                Ok(Formula::lit(self.mk_lit(minus, tok, true, val)?))
             }
-            _ => self.err(ErrorKind::UnexpectedToken),
+            _ => self.err_expected(ErrorKind::UnexpectedToken, Expected::OPEN | Expected::NAT),
         }
     }
 
@@ -248,47 +584,811 @@ where
     }
 
     fn parse_eq_formula(&mut self) -> Result<Formula> {
+        self.check_extension(Extensions::EQ, ErrorKind::IllegalEqExtensionUsed)?;
         self.expect(TokenKind::Eq)?;
         Ok(Formula::eq(self.parse_formula_params()?))
     }
 
     fn parse_xor_formula(&mut self) -> Result<Formula> {
+        self.check_extension(Extensions::XOR, ErrorKind::IllegalXorExtensionUsed)?;
         self.expect(TokenKind::Ident(Ident::Xor))?;
         Ok(Formula::xor(self.parse_formula_params()?))
     }
 
-    fn parse_dimacs(&mut self) -> Result<Instance> {
+    /// Parses a block of the quantifier prefix of a `.qdimacs` file.
+    ///
+    /// Every quantified variable must be within the declared number of variables
+    /// and must not have been quantified before as tracked by `quantified`.
+    fn parse_quantifier_block(
+        &mut self,
+        quantifier: Quantifier,
+        quantified: &mut HashSet<u64>,
+    ) -> Result<QuantifierBlock> {
+        self.consume()?;
+        let mut vars = Vec::new();
+        loop {
+            let tok = self.peek()?;
+            match tok.kind {
+                TokenKind::Zero => {
+                    self.consume()?;
+                    return Ok(QuantifierBlock::new(quantifier, vars));
+                }
+                TokenKind::Nat(val) => {
+                    if val > self.num_vars {
+                        return self.err(ErrorKind::TooManyVariables);
+                    }
+                    if !quantified.insert(val) {
+                        return self.err(ErrorKind::DuplicateQuantifiedVariable);
+                    }
+                    self.consume()?;
+                    vars.push(Var(val));
+                }
+                _ => {
+                    return self
+                        .err_expected(ErrorKind::UnexpectedToken, Expected::NAT | Expected::ZERO)
+                }
+            }
+        }
+    }
+
+    fn parse_qdimacs(&mut self) -> Result<QdimacsInstance> {
+        use self::Ident::{A, E};
+        self.consume()?;
+        self.expect(TokenKind::Ident(Ident::Problem))?;
+        let (num_vars, num_clauses) = self.parse_cnf_problem()?;
+        let mut prefix = Vec::new();
+        let mut quantified = HashSet::new();
+        loop {
+            let quantifier = match self.peek()?.kind {
+                TokenKind::Ident(E) => Quantifier::Exists,
+                TokenKind::Ident(A) => Quantifier::Forall,
+                _ => break,
+            };
+            prefix.push(self.parse_quantifier_block(quantifier, &mut quantified)?);
+        }
+        let clauses = self.parse_clauses(num_clauses)?;
+        if !self.is_at_eof() {
+            return self.err_expected(ErrorKind::NotParsedToEnd, Expected::END_OF_FILE);
+        }
+        Ok(QdimacsInstance::new(num_vars, prefix, clauses))
+    }
+
+    fn parse_icnf_problem(&mut self) -> Result<()> {
+        self.consume()?;
+        self.expect(TokenKind::Ident(Ident::Problem))?;
+        self.expect(TokenKind::Ident(Ident::Inccnf))?;
+        Ok(())
+    }
+
+    /// Parses the next clause or `a` line of assumptions of an `.icnf` file
+    /// unless the end of file is reached.
+    fn parse_icnf_step(&mut self) -> Result<Option<IncrementalStep>> {
+        match self.peek()?.kind {
+            TokenKind::EndOfFile => Ok(None),
+            TokenKind::Ident(Ident::A) => {
+                self.consume()?;
+                Ok(Some(IncrementalStep::solve(self.parse_lits()?)))
+            }
+            TokenKind::Minus | TokenKind::Nat(_) | TokenKind::Zero => {
+                Ok(Some(IncrementalStep::Add(self.parse_clause()?)))
+            }
+            _ => self.err_expected(
+                ErrorKind::UnexpectedToken,
+                Expected::NAT
+                    | Expected::MINUS
+                    | Expected::ZERO
+                    | Expected::A
+                    | Expected::END_OF_FILE,
+            ),
+        }
+    }
+
+    fn parse_icnf(&mut self) -> Result<IncrementalInstance> {
+        self.parse_icnf_problem()?;
+        let mut steps = Vec::new();
+        while let Some(step) = self.parse_icnf_step()? {
+            steps.push(step);
+        }
+        Ok(IncrementalInstance::new(steps))
+    }
+
+    /// Expects a natural number or zero.
+    fn expect_num(&mut self) -> Result<u64> {
+        match self.peek()?.kind {
+            TokenKind::Zero => {
+                self.consume()?;
+                Ok(0)
+            }
+            _ => self.expect_nat(),
+        }
+    }
+
+    /// Expects a possibly negative integer that fits into an `i64`.
+    fn expect_int(&mut self) -> Result<i64> {
+        let first = self.peek()?;
+        let negative = first.kind == TokenKind::Minus;
+        if negative {
+            self.consume()?;
+        }
+        let last = self.peek()?;
+        let val = self.expect_num()?;
+        if val > i64::MAX as u64 {
+            return Err(ParseError::new(first.loc, ErrorKind::IntOverflow)
+                .with_span(Span::new(first.span.start(), last.span.end())));
+        }
+        let val = val as i64;
+        Ok(if negative { -val } else { val })
+    }
+
+    fn parse_vertex(&mut self, num_vertices: u64) -> Result<u64> {
+        if let TokenKind::Nat(val) = self.peek()?.kind {
+            if val > num_vertices {
+                return self.err(ErrorKind::InvalidVertex);
+            }
+        }
+        self.expect_nat()
+    }
+
+    fn parse_vertex_weight(&mut self, num_vertices: u64) -> Result<VertexWeight> {
+        let vertex = self.parse_vertex(num_vertices)?;
+        Ok(VertexWeight::new(vertex, self.expect_num()?))
+    }
+
+    fn parse_graph(&mut self) -> Result<Graph> {
+        use self::Ident::{Col, Node, E};
+        self.consume()?;
+        self.expect(TokenKind::Ident(Ident::Problem))?;
+        let kind = match self.peek()?.kind {
+            TokenKind::Ident(Ident::Edge) => GraphKind::Edge,
+            TokenKind::Ident(Col) => GraphKind::Col,
+            _ => {
+                return self
+                    .err_expected(ErrorKind::UnexpectedToken, Expected::EDGE | Expected::COL)
+            }
+        };
+        self.consume()?;
+        let num_vertices = self.expect_nat()?;
+        let num_edges = self.expect_num()?;
+        let mut edges = Vec::with_capacity(reserved(num_edges));
+        let mut weights = Vec::new();
+        while !self.is_at_eof() {
+            match self.peek()?.kind {
+                TokenKind::Ident(E) => {
+                    self.consume()?;
+                    let u = self.parse_vertex(num_vertices)?;
+                    let v = self.parse_vertex(num_vertices)?;
+                    edges.push(Edge::new(u, v));
+                }
+                TokenKind::Ident(Node) => {
+                    self.consume()?;
+                    weights.push(self.parse_vertex_weight(num_vertices)?);
+                }
+                _ => {
+                    return self.err_expected(
+                        ErrorKind::UnexpectedToken,
+                        Expected::E | Expected::NODE | Expected::END_OF_FILE,
+                    )
+                }
+            }
+        }
+        Ok(Graph::new(kind, num_vertices, edges, weights))
+    }
+
+    /// Parses the number of nodes and arcs of the problem line of a network file
+    /// and returns the number of nodes.
+    fn parse_network_problem(&mut self) -> Result<u64> {
+        let num_nodes = self.expect_nat()?;
+        self.expect_num()?;
+        Ok(num_nodes)
+    }
+
+    /// Parses the start of an arc line and returns its tail and head nodes.
+    fn parse_arc(&mut self, num_nodes: u64) -> Result<(u64, u64)> {
+        self.expect(TokenKind::Ident(Ident::A))?;
+        let from = self.parse_vertex(num_nodes)?;
+        let to = self.parse_vertex(num_nodes)?;
+        Ok((from, to))
+    }
+
+    fn parse_shortest_path(&mut self) -> Result<Network> {
+        let num_nodes = self.parse_network_problem()?;
+        let mut arcs = Vec::new();
+        while !self.is_at_eof() {
+            let (from, to) = self.parse_arc(num_nodes)?;
+            arcs.push(WeightedArc::new(from, to, self.expect_num()?));
+        }
+        Ok(Network::shortest_path(num_nodes, arcs))
+    }
+
+    fn parse_max_flow(&mut self) -> Result<Network> {
+        use self::Ident::{Node, Sink, A, S};
+        let num_nodes = self.parse_network_problem()?;
+        let mut source = None;
+        let mut sink = None;
+        let mut arcs = Vec::new();
+        while !self.is_at_eof() {
+            match self.peek()?.kind {
+                TokenKind::Ident(Node) => {
+                    self.consume()?;
+                    let node = self.parse_vertex(num_nodes)?;
+                    let terminal = match self.peek()?.kind {
+                        TokenKind::Ident(S) => &mut source,
+                        TokenKind::Ident(Sink) => &mut sink,
+                        _ => {
+                            return self.err_expected(
+                                ErrorKind::UnexpectedToken,
+                                Expected::S | Expected::SINK,
+                            )
+                        }
+                    };
+                    if terminal.is_some() {
+                        return self.err(ErrorKind::InvalidFlowTerminals);
+                    }
+                    *terminal = Some(node);
+                    self.consume()?;
+                }
+                TokenKind::Ident(A) => {
+                    let (from, to) = self.parse_arc(num_nodes)?;
+                    arcs.push(CapacityArc::new(from, to, self.expect_num()?));
+                }
+                _ => {
+                    return self.err_expected(
+                        ErrorKind::UnexpectedToken,
+                        Expected::A | Expected::NODE | Expected::END_OF_FILE,
+                    )
+                }
+            }
+        }
+        match (source, sink) {
+            (Some(source), Some(sink)) => Ok(Network::max_flow(num_nodes, source, sink, arcs)),
+            _ => self.err(ErrorKind::InvalidFlowTerminals),
+        }
+    }
+
+    fn parse_min_cost_flow(&mut self) -> Result<Network> {
+        use self::Ident::{Node, A};
+        let num_nodes = self.parse_network_problem()?;
+        let mut supplies = Vec::new();
+        let mut arcs = Vec::new();
+        while !self.is_at_eof() {
+            match self.peek()?.kind {
+                TokenKind::Ident(Node) => {
+                    self.consume()?;
+                    let node = self.parse_vertex(num_nodes)?;
+                    supplies.push(NodeSupply::new(node, self.expect_int()?));
+                }
+                TokenKind::Ident(A) => {
+                    let (from, to) = self.parse_arc(num_nodes)?;
+                    let lower = self.expect_num()?;
+                    let capacity = self.expect_num()?;
+                    let cost = self.expect_int()?;
+                    arcs.push(CostArc::new(from, to, lower, capacity, cost));
+                }
+                _ => {
+                    return self.err_expected(
+                        ErrorKind::UnexpectedToken,
+                        Expected::A | Expected::NODE | Expected::END_OF_FILE,
+                    )
+                }
+            }
+        }
+        Ok(Network::min_cost_flow(num_nodes, supplies, arcs))
+    }
+
+    fn parse_network(&mut self) -> Result<Network> {
+        use self::Ident::{Max, Min, Sp};
+        self.consume()?;
+        self.expect(TokenKind::Ident(Ident::Problem))?;
+        match self.peek()?.kind {
+            TokenKind::Ident(Sp) => {
+                self.consume()?;
+                self.parse_shortest_path()
+            }
+            TokenKind::Ident(Max) => {
+                self.consume()?;
+                self.parse_max_flow()
+            }
+            TokenKind::Ident(Min) => {
+                self.consume()?;
+                self.parse_min_cost_flow()
+            }
+            _ => self.err_expected(
+                ErrorKind::UnexpectedToken,
+                Expected::SP | Expected::MAX | Expected::MIN,
+            ),
+        }
+    }
+
+    fn parse_status(&mut self) -> Result<Status> {
+        use self::Ident::{Satisfiable, Unknown, Unsatisfiable};
+        self.expect(TokenKind::Ident(Ident::S))?;
+        let status = match self.peek()?.kind {
+            TokenKind::Ident(Satisfiable) => Status::Satisfiable,
+            TokenKind::Ident(Unsatisfiable) => Status::Unsatisfiable,
+            TokenKind::Ident(Unknown) => Status::Unknown,
+            _ => {
+                return self.err_expected(
+                    ErrorKind::UnexpectedToken,
+                    Expected::SATISFIABLE | Expected::UNSATISFIABLE | Expected::UNKNOWN,
+                )
+            }
+        };
+        self.consume()?;
+        Ok(status)
+    }
+
+    /// Parses the literals of a `v` line until the terminating `0` or the next line.
+    ///
+    /// Returns `true` if the model has been terminated.
+    fn parse_values(&mut self, model: &mut Vec<Lit>, assigned: &mut HashSet<Var>) -> Result<bool> {
+        use self::TokenKind::{Minus, Nat, Zero};
+        self.expect(TokenKind::Ident(Ident::Values))?;
+        loop {
+            match self.peek()?.kind {
+                Minus | Nat(_) => {
+                    let (lit, loc, span) = self.parse_spanned_lit()?;
+                    if !assigned.insert(lit.var()) {
+                        return Err(
+                            ParseError::new(loc, ErrorKind::DuplicateModelVariable).with_span(span)
+                        );
+                    }
+                    model.push(lit);
+                }
+                Zero => {
+                    self.consume()?;
+                    return Ok(true);
+                }
+                _ => return Ok(false),
+            }
+        }
+    }
+
+    fn parse_solver_output(&mut self) -> Result<SolverOutput> {
+        use self::Ident::{Values, S};
+        use self::TokenKind::{EndOfFile, Ident};
+        self.consume()?;
+        let mut status = None;
+        let mut model = Vec::new();
+        let mut assigned = HashSet::new();
+        let mut has_values = false;
+        let mut terminated = false;
+        loop {
+            // Models are only given for satisfiable instances, after their status line.
+            let accepts_values = status == Some(Status::Satisfiable) && !terminated;
+            match self.peek()?.kind {
+                Ident(S) if status.is_none() => status = Some(self.parse_status()?),
+                Ident(Values) if accepts_values => {
+                    has_values = true;
+                    terminated = self.parse_values(&mut model, &mut assigned)?;
+                }
+                kind => {
+                    let mut expected = Expected::END_OF_FILE;
+                    if status.is_none() {
+                        expected |= Expected::S;
+                    }
+                    if accepts_values {
+                        expected |= Expected::VALUES;
+                    }
+                    if has_values && !terminated {
+                        expected |= Expected::NAT | Expected::MINUS | Expected::ZERO;
+                    }
+                    match (kind, status) {
+                        (EndOfFile, Some(status)) if terminated || !has_values => {
+                            return Ok(SolverOutput::new(status, model))
+                        }
+                        (EndOfFile, _) => {
+                            return self.err_expected(
+                                ErrorKind::UnexpectedEndOfFile,
+                                expected - Expected::END_OF_FILE,
+                            )
+                        }
+                        _ => return self.err_expected(ErrorKind::UnexpectedToken, expected),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Parses the next step of a textual DRAT proof unless the end of file is reached.
+    pub(crate) fn parse_drat_step(&mut self) -> Result<Option<ProofStep>> {
+        match self.peek()?.kind {
+            TokenKind::EndOfFile => Ok(None),
+            TokenKind::Ident(Ident::Delete) => {
+                self.consume()?;
+                Ok(Some(ProofStep::Delete(self.parse_clause()?)))
+            }
+            _ => Ok(Some(ProofStep::Add(self.parse_clause()?))),
+        }
+    }
+
+    /// Parses the next step of a textual LRAT proof unless the end of file is reached.
+    pub(crate) fn parse_lrat_step(&mut self) -> Result<Option<LratStep>> {
+        use self::TokenKind::{Minus, Nat, Zero};
+        if self.is_at_eof() {
+            return Ok(None);
+        }
+        let id = self.expect_nat()?;
+        if self.peek()?.kind == TokenKind::Ident(Ident::Delete) {
+            self.consume()?;
+            let mut ids = Vec::new();
+            loop {
+                match self.peek()?.kind {
+                    Nat(id) => {
+                        self.consume()?;
+                        ids.push(id);
+                    }
+                    Zero => {
+                        self.consume()?;
+                        return Ok(Some(LratStep::delete(ids)));
+                    }
+                    _ => return self.err_unexpected(Expected::NAT | Expected::ZERO),
+                }
+            }
+        }
+        let clause = self.parse_clause()?;
+        let mut hints = Vec::new();
+        loop {
+            match self.peek()?.kind {
+                Minus | Nat(_) => hints.push(self.expect_int()?),
+                Zero => {
+                    self.consume()?;
+                    return Ok(Some(LratStep::add(id, clause, hints)));
+                }
+                _ => return self.err_unexpected(Expected::NAT | Expected::MINUS | Expected::ZERO),
+            }
+        }
+    }
+
+    /// Parses a `.wcnf` file with or without `p wcnf` problem line.
+    fn parse_wcnf(&mut self) -> Result<Instance> {
         self.consume()?;
-        let instance = self.parse_header();
+        let instance = match self.peek()?.kind {
+            TokenKind::Ident(Ident::Problem) => {
+                self.consume()?;
+                self.parse_wcnf_header()?
+            }
+            _ => self.parse_wcnf_headerless()?,
+        };
+        if !self.is_at_eof() {
+            return self.err_expected(ErrorKind::NotParsedToEnd, Expected::END_OF_FILE);
+        }
+        Ok(instance)
+    }
+
+    pub(crate) fn parse_dimacs(&mut self) -> Result<Instance> {
+        self.consume()?;
+        let instance = match self.peek()?.kind {
+            TokenKind::Ident(Ident::Hard) => self.parse_wcnf_headerless()?,
+            _ => self.parse_header()?,
+        };
         if self.is_at_eof() {
-            instance
+            Ok(instance)
         } else {
-            self.err(ErrorKind::NotParsedToEnd)
+            self.err_expected(ErrorKind::NotParsedToEnd, Expected::END_OF_FILE)
         }
     }
 }
 
+/// The byte iterator used to parse from a byte source.
+pub(crate) type ReadBytes<R> = Bytes<BufReader<R>>;
+
+pub(crate) fn read_bytes<R: Read>(input: R) -> ReadBytes<R> {
+    BufReader::new(input).bytes()
+}
+
 /// Parses a the given string as `.cnf` or `.sat` file as specified in
 /// [DIMACS format specification](http://www.domagoj-babic.com/uploads/ResearchProjects/Spear/dimacs-cnf.pdf).
 ///
+/// Also parses `.wcnf` files of the MaxSAT Evaluations with a `p wcnf` problem line or in
+/// the header-less format if their first clause is a hard clause marked with `h`.
+/// Use `parse_wcnf` for header-less `.wcnf` files starting with a soft clause.
+///
 /// Returns an appropriate SAT instance if no errors occured while parsing.
 pub fn parse_dimacs(input: &str) -> Result<Instance> {
-    Parser::from(input.bytes()).parse_dimacs()
+    parse_dimacs_bytes(input.as_bytes())
+}
+
+/// Parses the given bytes just like `parse_dimacs`.
+///
+/// Well-formed `.cnf` inputs are handled by a fast path operating directly on the bytes.
+pub fn parse_dimacs_bytes(input: &[u8]) -> Result<Instance> {
+    match scan_cnf(input) {
+        Some(instance) => Ok(instance),
+        None => Parser::from(input.iter().cloned().map(Ok)).parse_dimacs(),
+    }
 }
 
 /// Parses a the given byte source as `.cnf` or `.sat` file as specified in
 /// [DIMACS format specification](http://www.domagoj-babic.com/uploads/ResearchProjects/Spear/dimacs-cnf.pdf).
 ///
 /// Returns an appropriate SAT instance if no errors occured while parsing.
+///
+/// The input is read through a buffer while parsing so it is never held in memory entirely.
+/// Inputs that already are in memory are parsed faster by `parse_dimacs_bytes`.
 pub fn read_dimacs<R: Read>(input: R) -> Result<Instance> {
-    Parser::from(input.bytes().map(|b| b.expect("IO Error"))).parse_dimacs()
+    Parser::from(read_bytes(input)).parse_dimacs()
 }
 
-#[cfg(test)]
-mod tests {
-    use std::io::Read;
+/// Parses the file at the given path as `.cnf` or `.sat` file just like `read_dimacs`.
+///
+/// Files compressed with gzip, xz, bzip2 or zstd are decompressed transparently if the
+/// corresponding cargo feature is enabled, otherwise `ErrorKind::UnsupportedCompression`
+/// is returned. The compression format is detected by the magic bytes of the file.
+pub fn read_dimacs_path<P: AsRef<Path>>(path: P) -> Result<Instance> {
+    read_dimacs(open_path(path.as_ref())?)
+}
 
-    use super::*;
+/// Parses the given bytes as `.cnf` file into an arena-backed `ClauseDb`.
+///
+/// Literals are converted into the literal type `L` of the clause database and
+/// `ErrorKind::LitOutOfRange` is returned for literals that are not representable.
+/// Well-formed inputs are scanned directly into the clause database.
+pub fn parse_clause_db<L: TryFrom<Lit>>(input: &[u8]) -> Result<ClauseDb<L>> {
+    let mut scanner = Scanner::new(input);
+    if let Some((num_vars, num_clauses)) = scanner.scan_header() {
+        let num_clauses = (num_clauses as usize).min(input.len() / 2);
+        let mut db = ClauseDb::with_capacity(num_vars, num_clauses, 0);
+        if scanner.scan_clauses(&mut db).is_some() {
+            return Ok(db);
+        }
+    }
+    Parser::from(input.iter().cloned().map(Ok)).parse_clause_db()
+}
+
+/// Parses the given byte source as `.cnf` file into an arena-backed `ClauseDb`
+/// just like `parse_clause_db`.
+///
+/// The input is read through a buffer while parsing just like in `read_dimacs`.
+pub fn read_clause_db<L: TryFrom<Lit>, R: Read>(input: R) -> Result<ClauseDb<L>> {
+    Parser::from(read_bytes(input)).parse_clause_db()
+}
+
+/// Parses a the given string just like `parse_dimacs` but additionally performs
+/// enhanced semantic checks on the parsed instance.
+///
+/// The enhanced checks verify that
+///
+/// - no literal refers to a variable beyond the number of variables declared in the problem line,
+/// - the number of clauses of a `.cnf` or `.wcnf` file matches the number declared in the
+///   problem line and
+/// - `xor(..)` and `=(..)` formulas of `.sat` files are only used with their declared extension.
+///
+/// Header-less `.wcnf` files declare nothing that could be checked. The checks are only
+/// available for the formats of `parse_dimacs` and `CnfReader::safe`: the other parsers
+/// like `parse_wcnf`, `parse_qdimacs` or `parse_icnf` never perform them.
+pub fn parse_safe_dimacs(input: &str) -> Result<Instance> {
+    Parser::safe(input.bytes().map(Ok)).parse_dimacs()
+}
+
+/// Parses a the given byte source just like `read_dimacs` but additionally performs
+/// the enhanced semantic checks of `parse_safe_dimacs`.
+pub fn read_safe_dimacs<R: Read>(input: R) -> Result<Instance> {
+    Parser::safe(read_bytes(input)).parse_dimacs()
+}
+
+/// Parses a the given string as `.wcnf` file of the MaxSAT Evaluations with a `p wcnf`
+/// problem line or in the header-less format introduced in 2022.
+///
+/// In contrast to `parse_dimacs` header-less files may start with a soft clause.
+pub fn parse_wcnf(input: &str) -> Result<Instance> {
+    Parser::from(input.bytes().map(Ok)).parse_wcnf()
+}
+
+/// Parses a the given byte source as `.wcnf` file just like `parse_wcnf`.
+pub fn read_wcnf<R: Read>(input: R) -> Result<Instance> {
+    Parser::from(read_bytes(input)).parse_wcnf()
+}
+
+/// Parses a the given string as `.qdimacs` file as specified in the
+/// [QDIMACS standard](http://www.qbflib.org/qdimacs.html).
+///
+/// The quantifier prefix is always checked for variables that are quantified more than
+/// once or that are beyond the number of variables declared in the problem line.
+pub fn parse_qdimacs(input: &str) -> Result<QdimacsInstance> {
+    Parser::from(input.bytes().map(Ok)).parse_qdimacs()
+}
+
+/// Parses a the given byte source as `.qdimacs` file just like `parse_qdimacs`.
+pub fn read_qdimacs<R: Read>(input: R) -> Result<QdimacsInstance> {
+    Parser::from(read_bytes(input)).parse_qdimacs()
+}
+
+/// Parses a the given string as incremental `.icnf` file with a `p inccnf` problem line
+/// as used by the incremental track of the SAT competitions and by cube-and-conquer solvers.
+///
+/// Clauses and `a` lines of assumptions are returned as steps in the order of the file.
+pub fn parse_icnf(input: &str) -> Result<IncrementalInstance> {
+    Parser::from(input.bytes().map(Ok)).parse_icnf()
+}
+
+/// Parses a the given byte source as `.icnf` file just like `parse_icnf`.
+pub fn read_icnf<R: Read>(input: R) -> Result<IncrementalInstance> {
+    Parser::from(read_bytes(input)).parse_icnf()
+}
+
+/// Parses a the given string as DIMACS graph file with a `p edge` or `p col` problem line
+/// followed by `e u v` edge lines and `n v w` vertex weight lines.
+///
+/// Vertices are always checked to be within the number of vertices declared in the problem line.
+pub fn parse_graph(input: &str) -> Result<Graph> {
+    Parser::from(input.bytes().map(Ok)).parse_graph()
+}
+
+/// Parses a the given byte source as DIMACS graph file just like `parse_graph`.
+pub fn read_graph<R: Read>(input: R) -> Result<Graph> {
+    Parser::from(read_bytes(input)).parse_graph()
+}
+
+/// Parses a the given string as DIMACS network file of the implementation challenges.
+///
+/// The problem line decides whether a shortest path (`p sp`), maximum flow (`p max`)
+/// or minimum cost flow (`p min`) problem is parsed.
+/// Nodes are always checked to be within the number of nodes declared in the problem line.
+pub fn parse_network(input: &str) -> Result<Network> {
+    Parser::from(input.bytes().map(Ok)).parse_network()
+}
+
+/// Parses a the given byte source as DIMACS network file just like `parse_network`.
+pub fn read_network<R: Read>(input: R) -> Result<Network> {
+    Parser::from(read_bytes(input)).parse_network()
+}
+
+/// Parses a the given string as output of a SAT solver in the format of the SAT competitions.
+///
+/// The output consists of comment lines starting with `c`, exactly one status line
+/// `s SATISFIABLE`, `s UNSATISFIABLE` or `s UNKNOWN` and, only after `s SATISFIABLE`,
+/// optional `v` lines with the literals of the model, the last of which is terminated
+/// by `0`. A model must not contain two literals of the same variable.
+pub fn parse_solver_output(input: &str) -> Result<SolverOutput> {
+    Parser::from(input.bytes().map(Ok)).parse_solver_output()
+}
+
+/// Parses a the given byte source as SAT solver output just like `parse_solver_output`.
+pub fn read_solver_output<R: Read>(input: R) -> Result<SolverOutput> {
+    Parser::from(read_bytes(input)).parse_solver_output()
+}
+
+/// Reads the clauses of a `.cnf` file one by one from a byte source.
+///
+/// In contrast to `read_dimacs` the clauses are never collected into an `Instance`
+/// which keeps memory consumption constant for arbitrarily large `.cnf` files.
+/// The problem line is parsed upon construction and its values are accessible
+/// before the first clause is read.
+///
+/// XOR clauses of `x` lines are reported as errors since they are no `Clause`s.
+///
+/// Iteration stops after the first error.
+#[derive(Debug)]
+pub struct CnfReader<R>
+where
+    R: Read,
+{
+    parser: Parser<ReadBytes<R>>,
+    num_vars: u64,
+    num_clauses: u64,
+    num_parsed: u64,
+    done: bool,
+}
+
+impl<R> CnfReader<R>
+where
+    R: Read,
+{
+    /// Creates a new clause reader for the given byte source and parses its problem line.
+    pub fn new(input: R) -> Result<CnfReader<R>> {
+        CnfReader::from_parser(Parser::from(read_bytes(input)))
+    }
+
+    /// Creates a new clause reader just like `new` that additionally performs
+    /// the enhanced semantic checks of `parse_safe_dimacs` on every clause.
+    pub fn safe(input: R) -> Result<CnfReader<R>> {
+        CnfReader::from_parser(Parser::safe(read_bytes(input)))
+    }
+
+    fn from_parser(mut parser: Parser<ReadBytes<R>>) -> Result<CnfReader<R>> {
+        parser.consume()?;
+        parser.expect(TokenKind::Ident(Ident::Problem))?;
+        let (num_vars, num_clauses) = parser.parse_cnf_problem()?;
+        Ok(CnfReader {
+            parser,
+            num_vars,
+            num_clauses,
+            num_parsed: 0,
+            done: false,
+        })
+    }
+
+    /// Returns the number of variables declared in the problem line.
+    pub fn num_vars(&self) -> u64 {
+        self.num_vars
+    }
+
+    /// Returns the number of clauses declared in the problem line.
+    pub fn num_clauses(&self) -> u64 {
+        self.num_clauses
+    }
+}
+
+impl<R> Iterator for CnfReader<R>
+where
+    R: Read,
+{
+    type Item = Result<Clause>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self
+            .parser
+            .parse_next_clause(self.num_parsed, self.num_clauses)
+        {
+            Ok(Some(clause)) => {
+                self.num_parsed += 1;
+                Some(Ok(clause))
+            }
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// Reads the steps of an `.icnf` file one by one from a byte source.
+///
+/// Just like `CnfReader` this never collects the steps which allows driving an
+/// incremental solver while the file is still being read.
+/// The problem line is parsed upon construction.
+///
+/// Iteration stops after the first error.
+#[derive(Debug)]
+pub struct IcnfReader<R>
+where
+    R: Read,
+{
+    parser: Parser<ReadBytes<R>>,
+    done: bool,
+}
+
+impl<R> IcnfReader<R>
+where
+    R: Read,
+{
+    /// Creates a new step reader for the given byte source and parses its problem line.
+    pub fn new(input: R) -> Result<IcnfReader<R>> {
+        let mut parser = Parser::from(read_bytes(input));
+        parser.parse_icnf_problem()?;
+        Ok(IcnfReader {
+            parser,
+            done: false,
+        })
+    }
+}
+
+impl<R> Iterator for IcnfReader<R>
+where
+    R: Read,
+{
+    type Item = Result<IncrementalStep>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let step = self.parser.parse_icnf_step();
+        self.done = !matches!(step, Ok(Some(_)));
+        step.transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
     #[test]
     fn simple_cnf_1() {
@@ -445,4 +1545,886 @@ mod tests {
         );
         assert_eq!(parsed, expected);
     }
+
+    #[test]
+    fn safe_cnf() {
+        let sample = r"
+			c Example CNF format file
+			c
+			p cnf 4 3
+			1 3 -4 0
+			4 0 2
+			-3";
+        assert_eq!(parse_safe_dimacs(sample), parse_dimacs(sample));
+        assert_eq!(read_safe_dimacs(sample.as_bytes()), parse_dimacs(sample));
+    }
+
+    #[test]
+    fn safe_cnf_too_many_variables() {
+        let sample = r"
+			p cnf 4 2
+			1 3 -4 0
+			2 -5 0";
+        assert!(parse_dimacs(sample).is_ok());
+        assert_eq!(
+            parse_safe_dimacs(sample),
+            Err(ParseError::new(Loc::new(4, 6), ErrorKind::TooManyVariables)
+                .with_span(Span::new(31, 33)))
+        );
+    }
+
+    #[test]
+    fn safe_cnf_too_many_clauses() {
+        let sample = r"
+			p cnf 4 2
+			1 3 -4 0
+			2 0
+			-1 0";
+        assert!(parse_dimacs(sample).is_ok());
+        assert_eq!(
+            parse_safe_dimacs(sample),
+            Err(ParseError::new(Loc::new(5, 4), ErrorKind::TooManyClauses)
+                .with_span(Span::new(36, 37))
+                .with_found(TokenKind::Minus)
+                .with_expected(Expected::END_OF_FILE))
+        );
+    }
+
+    #[test]
+    fn safe_cnf_too_few_clauses() {
+        let sample = r"
+			p cnf 4 3
+			1 3 -4 0
+			2 0
+";
+        assert!(parse_dimacs(sample).is_ok());
+        assert_eq!(
+            parse_safe_dimacs(sample),
+            Err(ParseError::new(Loc::new(4, 6), ErrorKind::TooFewClauses)
+                .with_span(Span::new(32, 32))
+                .with_found(TokenKind::EndOfFile)
+                .with_expected(Expected::NAT | Expected::MINUS | Expected::ZERO))
+        );
+    }
+
+    #[test]
+    fn safe_sat() {
+        let sample = r"
+			p satex 3
+			(*(xor(1 2) =(2 -3)))";
+        assert_eq!(parse_safe_dimacs(sample), parse_dimacs(sample));
+    }
+
+    #[test]
+    fn safe_sat_too_many_variables() {
+        let sample = r"
+			p sat 3
+			(*(+(1 -4) 2))";
+        assert!(parse_dimacs(sample).is_ok());
+        assert_eq!(
+            parse_safe_dimacs(sample),
+            Err(
+                ParseError::new(Loc::new(3, 11), ErrorKind::TooManyVariables)
+                    .with_span(Span::new(22, 24))
+            )
+        );
+    }
+
+    #[test]
+    fn safe_sat_illegal_extensions() {
+        let sample = r"
+			p sate 3
+			(*(xor(1 2) =(2 -3)))";
+        assert!(parse_dimacs(sample).is_ok());
+        assert_eq!(
+            parse_safe_dimacs(sample),
+            Err(
+                ParseError::new(Loc::new(3, 7), ErrorKind::IllegalXorExtensionUsed)
+                    .with_span(Span::new(19, 22))
+                    .with_found(TokenKind::Ident(Ident::Xor))
+            )
+        );
+        let sample = r"
+			p satx 3
+			(*(xor(1 2) =(2 -3)))";
+        assert!(parse_dimacs(sample).is_ok());
+        assert_eq!(
+            parse_safe_dimacs(sample),
+            Err(
+                ParseError::new(Loc::new(3, 16), ErrorKind::IllegalEqExtensionUsed)
+                    .with_span(Span::new(28, 29))
+                    .with_found(TokenKind::Eq)
+            )
+        );
+    }
+
+    #[test]
+    fn cnf_reader() {
+        let sample = r"
+			c Example CNF format file
+			c
+			p cnf 4 3
+			1 3 -4 0
+			4 0 2
+			-3";
+        let mut reader = CnfReader::new(sample.as_bytes()).expect("valid problem line");
+        assert_eq!(reader.num_vars(), 4);
+        assert_eq!(reader.num_clauses(), 3);
+        assert_eq!(
+            reader.next(),
+            Some(Ok(Clause::from_vec(vec![
+                Lit::from_i64(1),
+                Lit::from_i64(3),
+                Lit::from_i64(-4)
+            ])))
+        );
+        assert_eq!(
+            reader.next(),
+            Some(Ok(Clause::from_vec(vec![Lit::from_i64(4)])))
+        );
+        assert_eq!(
+            reader.next(),
+            Some(Ok(Clause::from_vec(vec![
+                Lit::from_i64(2),
+                Lit::from_i64(-3)
+            ])))
+        );
+        assert_eq!(reader.next(), None);
+        assert_eq!(reader.next(), None);
+    }
+
+    #[test]
+    fn cnf_reader_invalid_problem_line() {
+        let sample = r"
+			p sat 4
+			(1)";
+        assert_eq!(
+            CnfReader::new(sample.as_bytes()).err(),
+            Some(
+                ParseError::new(Loc::new(2, 6), ErrorKind::UnexpectedToken)
+                    .with_span(Span::new(6, 9))
+                    .with_found(TokenKind::Ident(Ident::Sat))
+                    .with_expected(Expected::CNF)
+            )
+        );
+    }
+
+    #[test]
+    fn cnf_reader_stops_after_error() {
+        let sample = r"
+			p cnf 4 3
+			1 3 -4 0
+			4 ( 0
+			-3 0";
+        let mut reader = CnfReader::new(sample.as_bytes()).expect("valid problem line");
+        assert!(reader.next().expect("first clause").is_ok());
+        assert_eq!(
+            reader.next(),
+            Some(Err(ParseError::new(
+                Loc::new(4, 6),
+                ErrorKind::UnexpectedToken
+            )
+            .with_span(Span::new(31, 32))
+            .with_found(TokenKind::Open)
+            .with_expected(
+                Expected::NAT | Expected::MINUS | Expected::ZERO | Expected::END_OF_FILE
+            )))
+        );
+        assert_eq!(reader.next(), None);
+    }
+
+    #[test]
+    fn safe_cnf_reader() {
+        let sample = r"
+			p cnf 4 3
+			1 3 -4 0
+			4 0";
+        let reader = CnfReader::safe(sample.as_bytes()).expect("valid problem line");
+        assert_eq!(
+            reader.collect::<Result<Vec<_>>>(),
+            Err(ParseError::new(Loc::new(4, 6), ErrorKind::TooFewClauses)
+                .with_span(Span::new(32, 32))
+                .with_found(TokenKind::EndOfFile)
+                .with_expected(Expected::NAT | Expected::MINUS | Expected::ZERO))
+        );
+    }
+
+    /// A byte source that fails after yielding all of its data.
+    struct FailingReader {
+        data: &'static [u8],
+    }
+
+    impl Read for FailingReader {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.data.is_empty() {
+                return Err(io::Error::new(io::ErrorKind::BrokenPipe, "broken pipe"));
+            }
+            let len = self.data.read(buf)?;
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn read_io_error() {
+        let reader = FailingReader {
+            data: b"p cnf 3 2\n1 -2 0\n3 ",
+        };
+        assert_eq!(
+            read_dimacs(reader),
+            Err(ParseError::new(
+                Loc::new(3, 3),
+                ErrorKind::Io(io::Error::from(io::ErrorKind::BrokenPipe).into())
+            )
+            .with_span(Span::new(19, 19)))
+        );
+    }
+
+    #[test]
+    fn cnf_reader_io_error() {
+        let reader = FailingReader {
+            data: b"p cnf 3 2\n1 -2 0\n3 -",
+        };
+        let mut reader = CnfReader::new(reader).expect("valid problem line");
+        assert!(reader.next().expect("first clause").is_ok());
+        assert_eq!(
+            reader.next(),
+            Some(Err(ParseError::new(
+                Loc::new(3, 4),
+                ErrorKind::Io(io::Error::from(io::ErrorKind::BrokenPipe).into())
+            )
+            .with_span(Span::new(20, 20))))
+        );
+        assert_eq!(reader.next(), None);
+    }
+
+    #[test]
+    fn read_path() {
+        match read_dimacs_path("bench/aim-50-1_6-yes1-4.cnf").expect("valid .cnf") {
+            Instance::Cnf {
+                num_vars, clauses, ..
+            } => {
+                assert_eq!(num_vars, 50);
+                assert_eq!(clauses.len(), 80);
+            }
+            _ => panic!("expected a .cnf instance"),
+        }
+        assert_eq!(
+            read_dimacs_path("bench/missing.cnf"),
+            Err(ParseError::new(
+                Loc::new(1, 1),
+                ErrorKind::Io(io::Error::from(io::ErrorKind::NotFound).into())
+            ))
+        );
+    }
+
+    #[test]
+    fn lit_overflow() {
+        let sample = r"
+			p cnf 1 2
+			9223372036854775807 0
+			-9223372036854775808 0";
+        assert_eq!(
+            parse_dimacs(sample),
+            Err(ParseError::new(Loc::new(4, 4), ErrorKind::LitOverflow)
+                .with_span(Span::new(42, 62)))
+        );
+        let sample = r"
+			p sat 1
+			(+(1 -9223372036854775807 9223372036854775808))";
+        assert_eq!(
+            parse_dimacs(sample),
+            Err(ParseError::new(Loc::new(3, 30), ErrorKind::LitOverflow)
+                .with_span(Span::new(41, 60)))
+        );
+    }
+
+    #[test]
+    fn nat_overflow() {
+        let sample = r"
+			p cnf 18446744073709551616 1
+			1 0";
+        assert_eq!(
+            parse_dimacs(sample),
+            Err(ParseError::new(Loc::new(2, 10), ErrorKind::NatOverflow)
+                .with_span(Span::new(10, 30)))
+        );
+    }
+
+    #[test]
+    fn huge_num_clauses() {
+        let sample = r"
+			p cnf 1 9223372036854775807
+			1 0";
+        assert_eq!(
+            parse_dimacs(sample),
+            Ok(Instance::cnf(
+                1,
+                vec![Clause::from_vec(vec![Lit::from_i64(1)])]
+            ))
+        );
+        assert_eq!(
+            parse_safe_dimacs(sample).map_err(|err| err.kind),
+            Err(ErrorKind::TooFewClauses)
+        );
+        let sample = r"
+			p wcnf 1 9223372036854775807 2
+			1 1 0";
+        assert_eq!(
+            parse_wcnf(sample),
+            Ok(Instance::wcnf(
+                1,
+                vec![WeightedClause::new(
+                    Weight::Soft(1),
+                    Clause::from_vec(vec![Lit::from_i64(1)])
+                )]
+            ))
+        );
+    }
+
+    #[test]
+    fn simple_wcnf() {
+        let sample = r"
+			c Sample DIMACS .wcnf file
+			p wcnf 4 4 16
+			16 1 -2 4 0
+			16 -1 -2 3 0
+			8 -2 -4 0
+			4 -3 2 0";
+        let parsed = parse_safe_dimacs(sample).expect("valid .wcnf");
+        let expected = Instance::wcnf(
+            4,
+            vec![
+                WeightedClause::new(
+                    Weight::Hard,
+                    Clause::from_vec(vec![Lit::from_i64(1), Lit::from_i64(-2), Lit::from_i64(4)]),
+                ),
+                WeightedClause::new(
+                    Weight::Hard,
+                    Clause::from_vec(vec![Lit::from_i64(-1), Lit::from_i64(-2), Lit::from_i64(3)]),
+                ),
+                WeightedClause::new(
+                    Weight::Soft(8),
+                    Clause::from_vec(vec![Lit::from_i64(-2), Lit::from_i64(-4)]),
+                ),
+                WeightedClause::new(
+                    Weight::Soft(4),
+                    Clause::from_vec(vec![Lit::from_i64(-3), Lit::from_i64(2)]),
+                ),
+            ],
+        );
+        assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn headerless_wcnf() {
+        let sample = r"
+			c Sample DIMACS .wcnf file of the 2022 format
+			h 1 -2 4 0
+			h -1 -2 3 0
+			8 -2 -4 0
+			4 -3 2 0";
+        let parsed = parse_safe_dimacs(sample).expect("valid .wcnf");
+        let expected = Instance::wcnf(
+            4,
+            vec![
+                WeightedClause::new(
+                    Weight::Hard,
+                    Clause::from_vec(vec![Lit::from_i64(1), Lit::from_i64(-2), Lit::from_i64(4)]),
+                ),
+                WeightedClause::new(
+                    Weight::Hard,
+                    Clause::from_vec(vec![Lit::from_i64(-1), Lit::from_i64(-2), Lit::from_i64(3)]),
+                ),
+                WeightedClause::new(
+                    Weight::Soft(8),
+                    Clause::from_vec(vec![Lit::from_i64(-2), Lit::from_i64(-4)]),
+                ),
+                WeightedClause::new(
+                    Weight::Soft(4),
+                    Clause::from_vec(vec![Lit::from_i64(-3), Lit::from_i64(2)]),
+                ),
+            ],
+        );
+        assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn safe_wcnf_too_many_clauses() {
+        let sample = r"
+			p wcnf 2 1 10
+			10 1 -2 0
+			3 2 0";
+        assert!(parse_dimacs(sample).is_ok());
+        assert_eq!(
+            parse_safe_dimacs(sample),
+            Err(ParseError::new(Loc::new(4, 4), ErrorKind::TooManyClauses)
+                .with_span(Span::new(34, 35))
+                .with_found(TokenKind::Nat(3))
+                .with_expected(Expected::END_OF_FILE))
+        );
+    }
+
+    #[test]
+    fn headerless_wcnf_starting_with_soft_clause() {
+        let sample = "8 -2 -4 0\nh 1 -2 0";
+        let expected = Instance::wcnf(
+            4,
+            vec![
+                WeightedClause::new(
+                    Weight::Soft(8),
+                    Clause::from_vec(vec![Lit::from_i64(-2), Lit::from_i64(-4)]),
+                ),
+                WeightedClause::new(
+                    Weight::Hard,
+                    Clause::from_vec(vec![Lit::from_i64(1), Lit::from_i64(-2)]),
+                ),
+            ],
+        );
+        assert_eq!(parse_wcnf(sample), Ok(expected.clone()));
+        assert_eq!(read_wcnf(sample.as_bytes()), Ok(expected));
+        assert_eq!(
+            parse_wcnf("p wcnf 2 1 10\n10 1 -2 0"),
+            parse_dimacs("p wcnf 2 1 10\n10 1 -2 0")
+        );
+        assert!(parse_wcnf("p cnf 2 1\n1 -2 0").is_err());
+    }
+
+    #[test]
+    fn headerless_cnf() {
+        assert_eq!(
+            parse_dimacs("1 -2 0"),
+            Err(ParseError::new(Loc::new(1, 1), ErrorKind::UnexpectedToken)
+                .with_span(Span::new(0, 1))
+                .with_found(TokenKind::Nat(1))
+                .with_expected(Expected::PROBLEM))
+        );
+    }
+
+    #[test]
+    fn headerless_wcnf_invalid_weight() {
+        let sample = r"
+			h 1 -2 0
+			-3 2 0";
+        assert_eq!(
+            parse_dimacs(sample),
+            Err(ParseError::new(Loc::new(3, 4), ErrorKind::UnexpectedToken)
+                .with_span(Span::new(16, 17))
+                .with_found(TokenKind::Minus)
+                .with_expected(Expected::HARD | Expected::NAT))
+        );
+    }
+
+    #[test]
+    fn simple_qdimacs() {
+        let sample = r"
+			c Sample QDIMACS file
+			p cnf 4 2
+			a 1 2 0
+			e 3 4 0
+			-1 2 3 0
+			1 -4 0";
+        let parsed = parse_qdimacs(sample).expect("valid .qdimacs");
+        let expected = QdimacsInstance::new(
+            4,
+            vec![
+                QuantifierBlock::new(Quantifier::Forall, vec![Var(1), Var(2)]),
+                QuantifierBlock::new(Quantifier::Exists, vec![Var(3), Var(4)]),
+            ],
+            vec![
+                Clause::from_vec(vec![Lit::from_i64(-1), Lit::from_i64(2), Lit::from_i64(3)]),
+                Clause::from_vec(vec![Lit::from_i64(1), Lit::from_i64(-4)]),
+            ],
+        );
+        assert_eq!(parsed, expected);
+        assert_eq!(read_qdimacs(sample.as_bytes()), Ok(expected));
+    }
+
+    #[test]
+    fn qdimacs_duplicate_quantified_variable() {
+        let sample = r"
+			p cnf 3 1
+			e 1 2 0
+			a 2 0
+			1 0";
+        assert_eq!(
+            parse_qdimacs(sample),
+            Err(
+                ParseError::new(Loc::new(4, 6), ErrorKind::DuplicateQuantifiedVariable)
+                    .with_span(Span::new(30, 31))
+                    .with_found(TokenKind::Nat(2))
+            )
+        );
+    }
+
+    #[test]
+    fn qdimacs_quantified_variable_out_of_range() {
+        let sample = r"
+			p cnf 3 1
+			e 1 4 0
+			1 0";
+        assert_eq!(
+            parse_qdimacs(sample),
+            Err(ParseError::new(Loc::new(3, 8), ErrorKind::TooManyVariables)
+                .with_span(Span::new(21, 22))
+                .with_found(TokenKind::Nat(4)))
+        );
+    }
+
+    #[test]
+    fn cnf_with_xors() {
+        let sample = r"
+			c Sample .cnf file with XOR clauses
+			p cnf 3 3
+			1 -2 0
+			x1 -2 3 0
+			x -3 2
+			0";
+        let lit = Lit::from_i64;
+        let expected = Instance::cnf_with_xors(
+            3,
+            vec![Clause::from_vec(vec![lit(1), lit(-2)])],
+            vec![
+                XorClause::from_vec(vec![lit(1), lit(-2), lit(3)]),
+                XorClause::from_vec(vec![lit(-3), lit(2)]),
+            ],
+        );
+        assert_eq!(parse_dimacs(sample), Ok(expected.clone()));
+        assert_eq!(parse_dimacs_bytes(sample.as_bytes()), Ok(expected.clone()));
+        assert_eq!(parse_safe_dimacs(sample), Ok(expected));
+    }
+
+    #[test]
+    fn cnf_with_xors_invalid() {
+        let sample = r"
+			p cnf 3 2
+			1 -2 0
+			x1 -2 3 0
+			x3 0";
+        assert_eq!(
+            parse_safe_dimacs(sample),
+            Err(ParseError::new(Loc::new(5, 4), ErrorKind::TooManyClauses)
+                .with_span(Span::new(40, 41))
+                .with_found(TokenKind::Ident(Ident::X))
+                .with_expected(Expected::END_OF_FILE))
+        );
+        let sample = r"
+			p cnf 3 1
+			x1 x2 0";
+        assert_eq!(
+            parse_dimacs(sample),
+            Err(ParseError::new(Loc::new(3, 7), ErrorKind::UnknownKeyword)
+                .with_span(Span::new(20, 22)))
+        );
+        let sample = r"
+			p wcnf 2 1
+			x1 2 0";
+        assert_eq!(
+            parse_dimacs(sample),
+            Err(ParseError::new(Loc::new(3, 4), ErrorKind::UnknownKeyword)
+                .with_span(Span::new(18, 20)))
+        );
+    }
+
+    #[test]
+    fn simple_icnf() {
+        let sample = r"
+			c Sample incremental CNF file
+			p inccnf
+			1 -2 0
+			a 2 0
+			-1 3 0
+			a -3 2 0
+			a 0
+			2";
+        let lit = Lit::from_i64;
+        let expected = IncrementalInstance::new(vec![
+            IncrementalStep::add(vec![lit(1), lit(-2)]),
+            IncrementalStep::solve(vec![lit(2)]),
+            IncrementalStep::add(vec![lit(-1), lit(3)]),
+            IncrementalStep::solve(vec![lit(-3), lit(2)]),
+            IncrementalStep::solve(vec![]),
+            IncrementalStep::add(vec![lit(2)]),
+        ]);
+        assert_eq!(parse_icnf(sample), Ok(expected.clone()));
+        assert_eq!(read_icnf(sample.as_bytes()), Ok(expected.clone()));
+        let steps: Result<Vec<_>> = IcnfReader::new(sample.as_bytes())
+            .expect("valid problem line")
+            .collect();
+        assert_eq!(steps.as_deref(), Ok(expected.steps()));
+        assert_eq!(expected.max_var(), 3);
+    }
+
+    #[test]
+    fn icnf_invalid() {
+        assert_eq!(
+            parse_icnf("p cnf 2 1\n1 0"),
+            Err(ParseError::new(Loc::new(1, 3), ErrorKind::UnexpectedToken)
+                .with_span(Span::new(2, 5))
+                .with_found(TokenKind::Ident(Ident::Cnf))
+                .with_expected(Expected::INCCNF))
+        );
+        let sample = r"
+			p inccnf
+			1 0
+			e 1 0";
+        assert_eq!(
+            parse_icnf(sample),
+            Err(ParseError::new(Loc::new(4, 4), ErrorKind::UnexpectedToken)
+                .with_span(Span::new(23, 24))
+                .with_found(TokenKind::Ident(Ident::E))
+                .with_expected(
+                    Expected::NAT
+                        | Expected::MINUS
+                        | Expected::ZERO
+                        | Expected::A
+                        | Expected::END_OF_FILE
+                ))
+        );
+        let mut reader = IcnfReader::new(sample.as_bytes()).expect("valid problem line");
+        assert!(matches!(reader.next(), Some(Ok(IncrementalStep::Add(_)))));
+        assert!(matches!(reader.next(), Some(Err(_))));
+        assert_eq!(reader.next(), None);
+    }
+
+    #[test]
+    fn simple_graph() {
+        let sample = r"
+			c Sample DIMACS graph file
+			p edge 4 3
+			n 1 10
+			n 4 0
+			e 1 2
+			e 2 3
+			e 4 1";
+        let parsed = parse_graph(sample).expect("valid graph");
+        let expected = Graph::new(
+            GraphKind::Edge,
+            4,
+            vec![Edge::new(1, 2), Edge::new(2, 3), Edge::new(4, 1)],
+            vec![VertexWeight::new(1, 10), VertexWeight::new(4, 0)],
+        );
+        assert_eq!(parsed, expected);
+        assert_eq!(read_graph(sample.as_bytes()), Ok(expected));
+    }
+
+    #[test]
+    fn simple_col() {
+        let sample = r"
+			p col 2 1
+			e 1 2";
+        assert_eq!(
+            parse_graph(sample),
+            Ok(Graph::new(GraphKind::Col, 2, vec![Edge::new(1, 2)], vec![]))
+        );
+        let sample = r"
+			p col 2 18446744073709551615
+			e 1 2";
+        assert_eq!(
+            parse_graph(sample),
+            Ok(Graph::new(GraphKind::Col, 2, vec![Edge::new(1, 2)], vec![]))
+        );
+    }
+
+    #[test]
+    fn graph_invalid_vertex() {
+        let sample = r"
+			p col 3 2
+			e 1 4
+			e 2 3";
+        assert_eq!(
+            parse_graph(sample),
+            Err(ParseError::new(Loc::new(3, 8), ErrorKind::InvalidVertex)
+                .with_span(Span::new(21, 22))
+                .with_found(TokenKind::Nat(4)))
+        );
+    }
+
+    #[test]
+    fn simple_shortest_path() {
+        let sample = r"
+			c Sample DIMACS shortest path file
+			p sp 3 3
+			a 1 2 7
+			a 2 3 0
+			a 3 1 12";
+        let parsed = parse_network(sample).expect("valid network");
+        let expected = Network::shortest_path(
+            3,
+            vec![
+                WeightedArc::new(1, 2, 7),
+                WeightedArc::new(2, 3, 0),
+                WeightedArc::new(3, 1, 12),
+            ],
+        );
+        assert_eq!(parsed, expected);
+        assert_eq!(read_network(sample.as_bytes()), Ok(expected));
+    }
+
+    #[test]
+    fn simple_max_flow() {
+        let sample = r"
+			p max 3 2
+			n 3 t
+			n 1 s
+			a 1 2 5
+			a 2 3 4";
+        assert_eq!(
+            parse_network(sample),
+            Ok(Network::max_flow(
+                3,
+                1,
+                3,
+                vec![CapacityArc::new(1, 2, 5), CapacityArc::new(2, 3, 4)]
+            ))
+        );
+    }
+
+    #[test]
+    fn max_flow_missing_sink() {
+        let sample = r"
+			p max 2 1
+			n 1 s
+			a 1 2 5";
+        assert_eq!(
+            parse_network(sample),
+            Err(
+                ParseError::new(Loc::new(4, 10), ErrorKind::InvalidFlowTerminals)
+                    .with_span(Span::new(33, 33))
+                    .with_found(TokenKind::EndOfFile)
+            )
+        );
+    }
+
+    #[test]
+    fn simple_min_cost_flow() {
+        let sample = r"
+			p min 3 2
+			n 1 4
+			n 3 -4
+			a 1 2 0 4 -2
+			a 2 3 1 5 3";
+        assert_eq!(
+            parse_network(sample),
+            Ok(Network::min_cost_flow(
+                3,
+                vec![NodeSupply::new(1, 4), NodeSupply::new(3, -4)],
+                vec![CostArc::new(1, 2, 0, 4, -2), CostArc::new(2, 3, 1, 5, 3)]
+            ))
+        );
+    }
+
+    #[test]
+    fn network_invalid_problem_kind() {
+        let sample = "p edge 2 1";
+        assert_eq!(
+            parse_network(sample),
+            Err(ParseError::new(Loc::new(1, 3), ErrorKind::UnexpectedToken)
+                .with_span(Span::new(2, 6))
+                .with_found(TokenKind::Ident(Ident::Edge))
+                .with_expected(Expected::SP | Expected::MAX | Expected::MIN))
+        );
+    }
+
+    #[test]
+    fn simple_solver_output() {
+        let sample = r"
+			c Sample SAT solver output
+			s SATISFIABLE
+			v 1 -2 3
+			v -4 0";
+        let parsed = parse_solver_output(sample).expect("valid solver output");
+        let expected = SolverOutput::new(
+            Status::Satisfiable,
+            vec![
+                Lit::from_i64(1),
+                Lit::from_i64(-2),
+                Lit::from_i64(3),
+                Lit::from_i64(-4),
+            ],
+        );
+        assert_eq!(parsed, expected);
+        assert_eq!(read_solver_output(sample.as_bytes()), Ok(expected));
+        assert_eq!(
+            parse_solver_output("c no model\ns UNSATISFIABLE\n"),
+            Ok(SolverOutput::new(Status::Unsatisfiable, vec![]))
+        );
+    }
+
+    #[test]
+    fn solver_output_unterminated_model() {
+        assert_eq!(
+            parse_solver_output("s SATISFIABLE\nv 1 -2"),
+            Err(
+                ParseError::new(Loc::new(2, 6), ErrorKind::UnexpectedEndOfFile)
+                    .with_span(Span::new(20, 20))
+                    .with_found(TokenKind::EndOfFile)
+                    .with_expected(
+                        Expected::VALUES | Expected::NAT | Expected::MINUS | Expected::ZERO
+                    )
+            )
+        );
+    }
+
+    #[test]
+    fn solver_output_missing_status() {
+        assert_eq!(
+            parse_solver_output(
+                "c no status
+"
+            ),
+            Err(
+                ParseError::new(Loc::new(0, 0), ErrorKind::UnexpectedEndOfFile)
+                    .with_span(Span::new(0, 0))
+                    .with_found(TokenKind::EndOfFile)
+                    .with_expected(Expected::S)
+            )
+        );
+    }
+
+    #[test]
+    fn solver_output_misplaced_values() {
+        assert_eq!(
+            parse_solver_output("v 1 2 0\ns SATISFIABLE"),
+            Err(ParseError::new(Loc::new(1, 1), ErrorKind::UnexpectedToken)
+                .with_span(Span::new(0, 1))
+                .with_found(TokenKind::Ident(Ident::Values))
+                .with_expected(Expected::S | Expected::END_OF_FILE))
+        );
+        assert_eq!(
+            parse_solver_output("s UNSATISFIABLE\nv 1 -2 0"),
+            Err(ParseError::new(Loc::new(2, 1), ErrorKind::UnexpectedToken)
+                .with_span(Span::new(16, 17))
+                .with_found(TokenKind::Ident(Ident::Values))
+                .with_expected(Expected::END_OF_FILE))
+        );
+        assert_eq!(
+            parse_solver_output("s UNKNOWN\nv 1 0")
+                .map_err(|err| err.kind)
+                .unwrap_err(),
+            ErrorKind::UnexpectedToken
+        );
+        assert_eq!(
+            parse_solver_output("s SATISFIABLE\nv 1 0\nv 2 0")
+                .map_err(|err| err.kind)
+                .unwrap_err(),
+            ErrorKind::UnexpectedToken
+        );
+    }
+
+    #[test]
+    fn solver_output_contradictory_model() {
+        assert_eq!(
+            parse_solver_output("s SATISFIABLE\nv 1 -1 0"),
+            Err(
+                ParseError::new(Loc::new(2, 5), ErrorKind::DuplicateModelVariable)
+                    .with_span(Span::new(18, 20))
+            )
+        );
+        assert_eq!(
+            parse_solver_output("s SATISFIABLE\nv 2 -3\nv 2 0")
+                .map_err(|err| err.kind)
+                .unwrap_err(),
+            ErrorKind::DuplicateModelVariable
+        );
+    }
 }
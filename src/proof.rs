@@ -0,0 +1,610 @@
+//! Streaming readers and writers of DRAT and LRAT proofs of unsatisfiability.
+//!
+//! The textual DRAT format consists of clause lines like those of `.cnf` files where
+//! deleted clauses are prefixed by `d`. The textual LRAT format additionally precedes every
+//! added clause by its identifier and follows it by the hints that derive it, terminated by `0`.
+//! Deletions list the identifiers of the deleted clauses after the identifier of the last
+//! added clause and `d`.
+//!
+//! The binary formats start every step with the byte `a` or `d`. Numbers are encoded
+//! with seven bits per byte, least significant group first, where the most significant bit
+//! marks that another byte follows. A signed number `x` is encoded as `2 * |x| + 1` if
+//! `x` is negative and as `2 * x` otherwise. Lists are terminated by an encoded `0`.
+//!
+//! Errors of binary proofs are located by their span of byte offsets only
+//! since binary proofs have no lines.
+
+use std::io::{self, Read, Write};
+
+use crate::errors::*;
+use crate::items::*;
+use crate::parser::{read_bytes, Parser, ReadBytes};
+
+#[derive(Debug)]
+struct BinaryReader<R>
+where
+    R: Read,
+{
+    bytes: ReadBytes<R>,
+
+    /// the byte offset of the next byte
+    offset: u64,
+}
+
+impl<R> BinaryReader<R>
+where
+    R: Read,
+{
+    fn from(input: R) -> BinaryReader<R> {
+        BinaryReader {
+            bytes: read_bytes(input),
+            offset: 0,
+        }
+    }
+
+    fn mk_err(&self, start: u64, kind: ErrorKind) -> ParseError {
+        ParseError::new(Loc::new(0, 0), kind).with_span(Span::new(start, self.offset))
+    }
+
+    fn next_byte(&mut self) -> Result<Option<u8>> {
+        match self.bytes.next() {
+            Some(Ok(byte)) => {
+                self.offset += 1;
+                Ok(Some(byte))
+            }
+            Some(Err(err)) => Err(self.mk_err(self.offset, ErrorKind::Io(err.into()))),
+            None => Ok(None),
+        }
+    }
+
+    fn read_num(&mut self) -> Result<u64> {
+        let start = self.offset;
+        let mut val: u64 = 0;
+        let mut shift = 0;
+        loop {
+            let byte = match self.next_byte()? {
+                Some(byte) => byte,
+                None => return Err(self.mk_err(start, ErrorKind::UnexpectedEndOfFile)),
+            };
+            let bits = u64::from(byte & 0x7F);
+            if shift >= 64 || (bits << shift) >> shift != bits {
+                return Err(self.mk_err(start, ErrorKind::NatOverflow));
+            }
+            val |= bits << shift;
+            if byte & 0x80 == 0 {
+                return Ok(val);
+            }
+            shift += 7;
+        }
+    }
+
+    /// Reads a signed number and returns `None` for the terminating `0`.
+    fn read_signed(&mut self, overflow: ErrorKind) -> Result<Option<i64>> {
+        let start = self.offset;
+        let encoded = self.read_num()?;
+        if encoded == 0 {
+            return Ok(None);
+        }
+        let val = encoded >> 1;
+        if val == 0 {
+            return Err(self.mk_err(start, ErrorKind::ExpectedLit));
+        }
+        if val > i64::MAX as u64 {
+            return Err(self.mk_err(start, overflow));
+        }
+        let val = val as i64;
+        Ok(Some(if encoded & 1 == 1 { -val } else { val }))
+    }
+
+    /// Reads a positive clause identifier and returns `None` for the terminating `0`.
+    fn read_id(&mut self) -> Result<Option<u64>> {
+        let start = self.offset;
+        match self.read_signed(ErrorKind::IntOverflow)? {
+            Some(id) if id < 0 => Err(self.mk_err(start, ErrorKind::ExpectedNat)),
+            id => Ok(id.map(|id| id as u64)),
+        }
+    }
+
+    fn read_clause(&mut self) -> Result<Clause> {
+        let mut lits = Vec::new();
+        while let Some(lit) = self.read_signed(ErrorKind::LitOverflow)? {
+            lits.push(Lit::from_i64(lit));
+        }
+        Ok(Clause::from_vec(lits))
+    }
+
+    /// Reads the byte that starts a step and returns `None` at the end of file.
+    fn read_step_start(&mut self) -> Result<Option<u8>> {
+        match self.next_byte()? {
+            Some(byte @ b'a') | Some(byte @ b'd') => Ok(Some(byte)),
+            Some(_) => Err(self.mk_err(self.offset - 1, ErrorKind::UnexpectedChar)),
+            None => Ok(None),
+        }
+    }
+
+    fn read_drat_step(&mut self) -> Result<Option<ProofStep>> {
+        match self.read_step_start()? {
+            Some(b'a') => Ok(Some(ProofStep::Add(self.read_clause()?))),
+            Some(_) => Ok(Some(ProofStep::Delete(self.read_clause()?))),
+            None => Ok(None),
+        }
+    }
+
+    fn read_lrat_step(&mut self) -> Result<Option<LratStep>> {
+        match self.read_step_start()? {
+            Some(b'a') => {
+                let start = self.offset;
+                let id = match self.read_id()? {
+                    Some(id) => id,
+                    None => return Err(self.mk_err(start, ErrorKind::ExpectedNat)),
+                };
+                let clause = self.read_clause()?;
+                let mut hints = Vec::new();
+                while let Some(hint) = self.read_signed(ErrorKind::IntOverflow)? {
+                    hints.push(hint);
+                }
+                Ok(Some(LratStep::add(id, clause, hints)))
+            }
+            Some(_) => {
+                let mut ids = Vec::new();
+                while let Some(id) = self.read_id()? {
+                    ids.push(id);
+                }
+                Ok(Some(LratStep::delete(ids)))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Source<R>
+where
+    R: Read,
+{
+    Text(Box<Parser<ReadBytes<R>>>),
+    Binary(BinaryReader<R>),
+}
+
+impl<R> Source<R>
+where
+    R: Read,
+{
+    fn text(input: R) -> Source<R> {
+        let mut parser = Parser::from(read_bytes(input));
+        // Errors of the first token are kept by the parser and reported by the first step.
+        let _ = parser.consume();
+        Source::Text(Box::new(parser))
+    }
+
+    fn binary(input: R) -> Source<R> {
+        Source::Binary(BinaryReader::from(input))
+    }
+}
+
+/// Yields the next step of `step` and stops after the end of file or the first error.
+fn next_step<T, F>(done: &mut bool, step: F) -> Option<Result<T>>
+where
+    F: FnOnce() -> Result<Option<T>>,
+{
+    if *done {
+        return None;
+    }
+    let next = step().transpose();
+    if !matches!(next, Some(Ok(_))) {
+        *done = true;
+    }
+    next
+}
+
+/// Reads the steps of a DRAT proof one by one from a byte source.
+///
+/// Iteration stops after the first error.
+#[derive(Debug)]
+pub struct DratReader<R>
+where
+    R: Read,
+{
+    source: Source<R>,
+    done: bool,
+}
+
+impl<R> DratReader<R>
+where
+    R: Read,
+{
+    /// Creates a new reader of a textual DRAT proof.
+    pub fn text(input: R) -> DratReader<R> {
+        DratReader {
+            source: Source::text(input),
+            done: false,
+        }
+    }
+
+    /// Creates a new reader of a binary DRAT proof.
+    pub fn binary(input: R) -> DratReader<R> {
+        DratReader {
+            source: Source::binary(input),
+            done: false,
+        }
+    }
+}
+
+impl<R> Iterator for DratReader<R>
+where
+    R: Read,
+{
+    type Item = Result<ProofStep>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let source = &mut self.source;
+        next_step(&mut self.done, || match *source {
+            Source::Text(ref mut parser) => parser.parse_drat_step(),
+            Source::Binary(ref mut reader) => reader.read_drat_step(),
+        })
+    }
+}
+
+/// Reads the steps of a LRAT proof one by one from a byte source.
+///
+/// Iteration stops after the first error.
+#[derive(Debug)]
+pub struct LratReader<R>
+where
+    R: Read,
+{
+    source: Source<R>,
+    done: bool,
+}
+
+impl<R> LratReader<R>
+where
+    R: Read,
+{
+    /// Creates a new reader of a textual LRAT proof.
+    pub fn text(input: R) -> LratReader<R> {
+        LratReader {
+            source: Source::text(input),
+            done: false,
+        }
+    }
+
+    /// Creates a new reader of a binary LRAT proof.
+    pub fn binary(input: R) -> LratReader<R> {
+        LratReader {
+            source: Source::binary(input),
+            done: false,
+        }
+    }
+}
+
+impl<R> Iterator for LratReader<R>
+where
+    R: Read,
+{
+    type Item = Result<LratStep>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let source = &mut self.source;
+        next_step(&mut self.done, || match *source {
+            Source::Text(ref mut parser) => parser.parse_lrat_step(),
+            Source::Binary(ref mut reader) => reader.read_lrat_step(),
+        })
+    }
+}
+
+fn write_num<W: Write>(out: &mut W, mut val: u64) -> io::Result<()> {
+    while val > 0x7F {
+        out.write_all(&[(val & 0x7F) as u8 | 0x80])?;
+        val >>= 7;
+    }
+    out.write_all(&[val as u8])
+}
+
+fn write_signed<W: Write>(out: &mut W, val: i64) -> io::Result<()> {
+    write_num(out, (val.unsigned_abs() << 1) | (val < 0) as u64)
+}
+
+fn write_text_list<W: Write>(out: &mut W, vals: &[i64]) -> io::Result<()> {
+    for val in vals {
+        write!(out, "{} ", val)?;
+    }
+    write!(out, "0")
+}
+
+fn write_binary_list<W: Write>(out: &mut W, vals: &[i64]) -> io::Result<()> {
+    for &val in vals {
+        write_signed(out, val)?;
+    }
+    write_num(out, 0)
+}
+
+fn lit_vals(clause: &Clause) -> Vec<i64> {
+    clause.lits().iter().map(|lit| lit.to_i64()).collect()
+}
+
+/// Writes the steps of a DRAT proof one by one.
+///
+/// The output is unbuffered, so wrapping `out` in a `BufWriter` is recommended.
+#[derive(Debug)]
+pub struct DratWriter<W>
+where
+    W: Write,
+{
+    out: W,
+    binary: bool,
+}
+
+impl<W> DratWriter<W>
+where
+    W: Write,
+{
+    /// Creates a new writer of a textual DRAT proof.
+    pub fn text(out: W) -> DratWriter<W> {
+        DratWriter { out, binary: false }
+    }
+
+    /// Creates a new writer of a binary DRAT proof.
+    pub fn binary(out: W) -> DratWriter<W> {
+        DratWriter { out, binary: true }
+    }
+
+    /// Writes the given step.
+    pub fn write_step(&mut self, step: &ProofStep) -> io::Result<()> {
+        let (start, clause) = match *step {
+            ProofStep::Add(ref clause) => (b'a', clause),
+            ProofStep::Delete(ref clause) => (b'd', clause),
+        };
+        if self.binary {
+            self.out.write_all(&[start])?;
+            write_binary_list(&mut self.out, &lit_vals(clause))
+        } else {
+            if start == b'd' {
+                write!(self.out, "d ")?;
+            }
+            write_text_list(&mut self.out, &lit_vals(clause))?;
+            writeln!(self.out)
+        }
+    }
+
+    /// Flushes the underlying writer.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// Writes the steps of a LRAT proof one by one.
+///
+/// Deletions of textual proofs are written with the identifier of the last added clause
+/// or with the number of clauses of the instance before the first clause is added.
+/// The output is unbuffered, so wrapping `out` in a `BufWriter` is recommended.
+#[derive(Debug)]
+pub struct LratWriter<W>
+where
+    W: Write,
+{
+    out: W,
+    binary: bool,
+    last_id: u64,
+}
+
+impl<W> LratWriter<W>
+where
+    W: Write,
+{
+    /// Creates a new writer of a textual LRAT proof for an instance with the given
+    /// number of clauses.
+    pub fn text(out: W, num_clauses: u64) -> LratWriter<W> {
+        LratWriter {
+            out,
+            binary: false,
+            last_id: num_clauses,
+        }
+    }
+
+    /// Creates a new writer of a binary LRAT proof.
+    pub fn binary(out: W) -> LratWriter<W> {
+        LratWriter {
+            out,
+            binary: true,
+            last_id: 0,
+        }
+    }
+
+    /// Writes the given step.
+    pub fn write_step(&mut self, step: &LratStep) -> io::Result<()> {
+        match *step {
+            LratStep::Add {
+                id,
+                ref clause,
+                ref hints,
+            } => {
+                self.last_id = id;
+                if self.binary {
+                    self.out.write_all(b"a")?;
+                    write_num(&mut self.out, id << 1)?;
+                    write_binary_list(&mut self.out, &lit_vals(clause))?;
+                    write_binary_list(&mut self.out, hints)
+                } else {
+                    write!(self.out, "{} ", id)?;
+                    write_text_list(&mut self.out, &lit_vals(clause))?;
+                    write!(self.out, " ")?;
+                    write_text_list(&mut self.out, hints)?;
+                    writeln!(self.out)
+                }
+            }
+            LratStep::Delete { ref ids } => {
+                let ids: Vec<i64> = ids.iter().map(|&id| id as i64).collect();
+                if self.binary {
+                    self.out.write_all(b"d")?;
+                    write_binary_list(&mut self.out, &ids)
+                } else {
+                    write!(self.out, "{} d ", self.last_id)?;
+                    write_text_list(&mut self.out, &ids)?;
+                    writeln!(self.out)
+                }
+            }
+        }
+    }
+
+    /// Flushes the underlying writer.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::lexer::TokenKind;
+
+    fn clause(lits: &[i64]) -> Clause {
+        Clause::from_vec(lits.iter().cloned().map(Lit::from_i64).collect())
+    }
+
+    fn drat_steps() -> Vec<ProofStep> {
+        vec![
+            ProofStep::Add(clause(&[1, -2])),
+            ProofStep::Delete(clause(&[-3, 129])),
+            ProofStep::Add(clause(&[-63])),
+            ProofStep::Add(clause(&[])),
+        ]
+    }
+
+    #[test]
+    fn text_drat() {
+        let sample = r"
+			c Sample DRAT proof
+			1 -2 0
+			d -3 129 0
+			-63 0
+			0";
+        let steps: Result<Vec<_>> = DratReader::text(sample.as_bytes()).collect();
+        assert_eq!(steps, Ok(drat_steps()));
+
+        let mut buf = Vec::new();
+        let mut writer = DratWriter::text(&mut buf);
+        for step in &drat_steps() {
+            writer.write_step(step).expect("valid write");
+        }
+        assert_eq!(buf, b"1 -2 0\nd -3 129 0\n-63 0\n0\n");
+    }
+
+    #[test]
+    fn binary_drat() {
+        let sample = [
+            b'a', 0x02, 0x05, 0x00, b'd', 0x07, 0x82, 0x02, 0x00, b'a', 0x7F, 0x00, b'a', 0x00,
+        ];
+        let steps: Result<Vec<_>> = DratReader::binary(&sample[..]).collect();
+        assert_eq!(steps, Ok(drat_steps()));
+
+        let mut buf = Vec::new();
+        let mut writer = DratWriter::binary(&mut buf);
+        for step in &drat_steps() {
+            writer.write_step(step).expect("valid write");
+        }
+        assert_eq!(buf, &sample[..]);
+    }
+
+    #[test]
+    fn binary_drat_errors() {
+        let mut reader = DratReader::binary(&[b'a', 0x02, b'x'][..]);
+        assert_eq!(
+            reader.next(),
+            Some(Err(ParseError::new(
+                Loc::new(0, 0),
+                ErrorKind::UnexpectedEndOfFile
+            )
+            .with_span(Span::new(3, 3))))
+        );
+        assert_eq!(reader.next(), None);
+
+        let mut reader = DratReader::binary(&[b'a', 0x02, 0x00, b'x'][..]);
+        assert_eq!(reader.next(), Some(Ok(ProofStep::Add(clause(&[1])))));
+        assert_eq!(
+            reader.next(),
+            Some(Err(ParseError::new(
+                Loc::new(0, 0),
+                ErrorKind::UnexpectedChar
+            )
+            .with_span(Span::new(3, 4))))
+        );
+        assert_eq!(reader.next(), None);
+    }
+
+    fn lrat_steps() -> Vec<LratStep> {
+        vec![
+            LratStep::add(5, clause(&[1, -2]), vec![1, 3]),
+            LratStep::delete(vec![1, 3]),
+            LratStep::add(6, clause(&[]), vec![5, -2, 4]),
+        ]
+    }
+
+    #[test]
+    fn text_lrat() {
+        let sample = r"
+			5 1 -2 0 1 3 0
+			5 d 1 3 0
+			6 0 5 -2 4 0";
+        let steps: Result<Vec<_>> = LratReader::text(sample.as_bytes()).collect();
+        assert_eq!(steps, Ok(lrat_steps()));
+
+        let mut buf = Vec::new();
+        let mut writer = LratWriter::text(&mut buf, 4);
+        for step in &lrat_steps() {
+            writer.write_step(step).expect("valid write");
+        }
+        assert_eq!(buf, b"5 1 -2 0 1 3 0\n5 d 1 3 0\n6 0 5 -2 4 0\n");
+    }
+
+    #[test]
+    fn text_lrat_starting_with_deletion() {
+        let steps = vec![
+            LratStep::delete(vec![2]),
+            LratStep::add(5, clause(&[1]), vec![1, 3]),
+        ];
+        let mut buf = Vec::new();
+        let mut writer = LratWriter::text(&mut buf, 4);
+        for step in &steps {
+            writer.write_step(step).expect("valid write");
+        }
+        assert_eq!(buf, b"4 d 2 0\n5 1 0 1 3 0\n");
+        let read: Result<Vec<_>> = LratReader::text(&buf[..]).collect();
+        assert_eq!(read, Ok(steps));
+    }
+
+    #[test]
+    fn text_lrat_missing_hints() {
+        let mut reader = LratReader::text(&b"5 1 -2 0 1 3"[..]);
+        assert_eq!(
+            reader.next(),
+            Some(Err(ParseError::new(
+                Loc::new(1, 12),
+                ErrorKind::UnexpectedEndOfFile
+            )
+            .with_span(Span::new(12, 12))
+            .with_found(TokenKind::EndOfFile)
+            .with_expected(Expected::NAT | Expected::MINUS | Expected::ZERO)))
+        );
+        assert_eq!(reader.next(), None);
+    }
+
+    #[test]
+    fn binary_lrat() {
+        let mut buf = Vec::new();
+        let mut writer = LratWriter::binary(&mut buf);
+        for step in &lrat_steps() {
+            writer.write_step(step).expect("valid write");
+        }
+        assert_eq!(
+            buf,
+            &[
+                b'a', 0x0A, 0x02, 0x05, 0x00, 0x02, 0x06, 0x00, b'd', 0x02, 0x06, 0x00, b'a', 0x0C,
+                0x00, 0x0A, 0x05, 0x08, 0x00
+            ][..]
+        );
+        let steps: Result<Vec<_>> = LratReader::binary(&buf[..]).collect();
+        assert_eq!(steps, Ok(lrat_steps()));
+    }
+}
@@ -0,0 +1,293 @@
+//! A fast path for scanning `.cnf` inputs that are entirely held in memory.
+//!
+//! The scanner operates directly on byte slices, skips comment lines as a whole
+//! and parses literals digit by digit without overflow checks whenever the number is
+//! short enough. It does not track locations and only accepts the common, well-formed
+//! subset of `.cnf` files: whenever it encounters anything unusual it gives up so that
+//! the regular `Parser` handles the input and reports precise errors instead.
+
+use crate::items::*;
+
+/// The maximum number of decimal digits that always fit into an `i64`.
+const MAX_SAFE_DIGITS: usize = 18;
+
+#[derive(Debug, Clone)]
+pub(crate) struct Scanner<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    pub(crate) fn new(input: &'a [u8]) -> Scanner<'a> {
+        Scanner { input, pos: 0 }
+    }
+
+    /// Returns the byte offset of the scanner within its input.
+    #[cfg(feature = "parallel")]
+    pub(crate) fn pos(&self) -> usize {
+        self.pos
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.pos).cloned()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<u8> {
+        self.input.get(self.pos + offset).cloned()
+    }
+
+    /// Returns `true` if the next token is a comment, i.e. a lone `c` keyword.
+    fn at_comment(&self) -> bool {
+        self.peek() == Some(b'c')
+            && !matches!(self.peek_at(1), Some(peeked) if peeked.is_ascii_alphanumeric())
+    }
+
+    /// Skips to the end of the line or to a `\0` byte which the `Lexer` treats as end of file.
+    fn skip_line(&mut self) {
+        let line = &self.input[self.pos..];
+        self.pos = match line.iter().position(|&byte| byte == b'\n' || byte == b'\0') {
+            Some(offset) => self.pos + offset,
+            None => self.input.len(),
+        };
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(peeked) if peeked.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    /// Skips whitespace and comments.
+    fn skip_blank(&mut self) {
+        loop {
+            self.skip_whitespace();
+            if !self.at_comment() {
+                return;
+            }
+            self.skip_line();
+        }
+    }
+
+    /// Returns `true` if the current token ends at the current position.
+    fn at_token_end(&self) -> bool {
+        !matches!(self.peek(), Some(peeked) if !peeked.is_ascii_whitespace())
+    }
+
+    /// Scans the given keyword followed by whitespace.
+    fn scan_keyword(&mut self, keyword: &[u8]) -> Option<()> {
+        if !self.input[self.pos..].starts_with(keyword) {
+            return None;
+        }
+        self.pos += keyword.len();
+        if !self.at_token_end() {
+            return None;
+        }
+        self.skip_whitespace();
+        Some(())
+    }
+
+    /// Scans a positive number that fits into an `i64`.
+    fn scan_nat(&mut self) -> Option<u64> {
+        let digits = self.input[self.pos..]
+            .iter()
+            .take_while(|byte| byte.is_ascii_digit())
+            .count();
+        if digits == 0 || self.input[self.pos] == b'0' {
+            return None;
+        }
+        let bytes = &self.input[self.pos..self.pos + digits];
+        let val = if digits <= MAX_SAFE_DIGITS {
+            bytes
+                .iter()
+                .fold(0, |val, &digit| val * 10 + (digit - b'0') as u64)
+        } else {
+            bytes.iter().try_fold(0u64, |val, &digit| {
+                val.checked_mul(10)?.checked_add((digit - b'0') as u64)
+            })?
+        };
+        self.pos += digits;
+        if val > i64::MAX as u64 || !self.at_token_end() {
+            return None;
+        }
+        Some(val)
+    }
+
+    /// Scans the `p cnf <num_vars> <num_clauses>` problem line preceded by comments.
+    pub(crate) fn scan_header(&mut self) -> Option<(u64, u64)> {
+        self.skip_blank();
+        self.scan_keyword(b"p")?;
+        self.scan_keyword(b"cnf")?;
+        let num_vars = self.scan_nat()?;
+        self.skip_whitespace();
+        let num_clauses = self.scan_nat()?;
+        Some((num_vars, num_clauses))
+    }
+
+    /// Scans clauses into the given sink until the end of the input, where the last clause
+    /// may omit its terminating `0`.
+    pub(crate) fn scan_clauses<S: ClauseSink>(&mut self, sink: &mut S) -> Option<()> {
+        let mut open = false;
+        loop {
+            self.skip_blank();
+            match self.peek() {
+                None => {
+                    if open {
+                        sink.end_clause();
+                    }
+                    return Some(());
+                }
+                Some(b'0') => {
+                    self.pos += 1;
+                    if !self.at_token_end() {
+                        return None;
+                    }
+                    sink.end_clause();
+                    open = false;
+                }
+                Some(b'-') => {
+                    self.pos += 1;
+                    sink.push_lit(Lit::from_i64(-(self.scan_nat()? as i64)))?;
+                    open = true;
+                }
+                Some(_) => {
+                    sink.push_lit(Lit::from_i64(self.scan_nat()? as i64))?;
+                    open = true;
+                }
+            }
+        }
+    }
+}
+
+/// Receives the literals and clause terminators scanned by a `Scanner`.
+pub(crate) trait ClauseSink {
+    /// Appends a literal to the current clause or returns `None` if it is not representable.
+    fn push_lit(&mut self, lit: Lit) -> Option<()>;
+
+    /// Finishes the current clause.
+    fn end_clause(&mut self);
+}
+
+/// Collects scanned clauses into separately allocated `Clause`s.
+#[derive(Debug, Default)]
+pub(crate) struct ClauseVec {
+    lits: Vec<Lit>,
+    pub(crate) clauses: Vec<Clause>,
+}
+
+impl ClauseSink for ClauseVec {
+    fn push_lit(&mut self, lit: Lit) -> Option<()> {
+        self.lits.push(lit);
+        Some(())
+    }
+
+    fn end_clause(&mut self) {
+        // Copying the reused buffer allocates exactly once per clause.
+        self.clauses.push(Clause::from_vec(self.lits.to_vec()));
+        self.lits.clear();
+    }
+}
+
+/// Returns `true` if the last token of the given line of a clause section is
+/// the `0` terminating a clause.
+#[cfg(feature = "parallel")]
+pub(crate) fn ends_clause(line: &[u8]) -> bool {
+    let mut scanner = Scanner::new(line);
+    let mut ends_clause = false;
+    loop {
+        scanner.skip_blank();
+        match scanner.peek() {
+            None => return ends_clause,
+            Some(b'0') => {
+                scanner.pos += 1;
+                if !scanner.at_token_end() {
+                    return false;
+                }
+                ends_clause = true;
+            }
+            Some(b'-') => {
+                scanner.pos += 1;
+                if scanner.scan_nat().is_none() {
+                    return false;
+                }
+                ends_clause = false;
+            }
+            Some(_) => {
+                if scanner.scan_nat().is_none() {
+                    return false;
+                }
+                ends_clause = false;
+            }
+        }
+    }
+}
+
+/// Scans the given input as `.cnf` file.
+///
+/// Returns `None` if the input is not a well-formed `.cnf` file within
+/// the subset handled by the scanner.
+pub(crate) fn scan_cnf(input: &[u8]) -> Option<Instance> {
+    let mut scanner = Scanner::new(input);
+    let (num_vars, num_clauses) = scanner.scan_header()?;
+    // Every clause takes at least two bytes which bounds the preallocation for bogus headers.
+    let mut clauses = ClauseVec {
+        lits: Vec::new(),
+        clauses: Vec::with_capacity((num_clauses as usize).min(input.len() / 2)),
+    };
+    scanner.scan_clauses(&mut clauses)?;
+    Some(Instance::cnf(num_vars, clauses.clauses))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::parser::Parser;
+
+    #[test]
+    fn scan_simple_cnf() {
+        let sample = "c comment\np cnf 3 3\n1 -2 0\nc inner comment\n\t2 3 0 -1\n-3 0\n";
+        let parsed = Parser::from(sample.bytes().map(Ok)).parse_dimacs();
+        assert_eq!(scan_cnf(sample.as_bytes()), parsed.ok());
+        assert_eq!(
+            scan_cnf(b"p cnf 2 2\n1 0\n-2"),
+            Some(Instance::cnf(
+                2,
+                vec![
+                    Clause::from_vec(vec![Lit::from_i64(1)]),
+                    Clause::from_vec(vec![Lit::from_i64(-2)])
+                ]
+            ))
+        );
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn lines_ending_clauses() {
+        assert!(ends_clause(b"1 -2 0"));
+        assert!(ends_clause(b"\t0  c comment 1"));
+        assert!(!ends_clause(b"1 -2"));
+        assert!(!ends_clause(b"c 1 -2 0"));
+        assert!(!ends_clause(b"1 - 2 0"));
+        assert!(!ends_clause(b""));
+    }
+
+    #[test]
+    fn scan_unusual_cnf() {
+        for sample in &[
+            "",
+            "p sat 2 (1)",
+            "p cnf 0 1\n1 0",
+            "p cnf 2 1\n- 1 0",
+            "p cnf 2 1\n1 -0",
+            "p cnf 2 1\n1 02",
+            "p cnf 2 1\n1c 0",
+            "p cnf 2 1\ncnf 0",
+            "p cnf 2 1\nx1 -2 0",
+            "c \0\np cnf 1 1\n1 0",
+            "p cnf 1 1\n9223372036854775808 0",
+            "p cnf 1 1\n99999999999999999999 0",
+        ] {
+            assert_eq!(scan_cnf(sample.as_bytes()), None, "{:?}", sample);
+        }
+    }
+}
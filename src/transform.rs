@@ -0,0 +1,787 @@
+//! The transformation of `.sat` formulas into equisatisfiable clauses of `.cnf` instances.
+//!
+//! Every subformula that is not a literal is represented by a fresh auxiliary variable
+//! above the variables of the instance. The auxiliary variable is defined by clauses over
+//! the literals that represent the inner formulas of the subformula.
+//!
+//! The Tseitin encoding defines every auxiliary variable to be equivalent to its subformula
+//! whereas the Plaisted–Greenbaum encoding only emits the implications required by the
+//! polarity in which the subformula occurs and thus produces fewer clauses.
+//!
+//! XOR clauses of `.cnf` instances are expanded into the clauses that forbid every
+//! assignment of even parity. Long XOR clauses are first cut into a chain of short ones
+//! linked by auxiliary cutting variables as the expansion grows exponentially in their length.
+
+use std::error;
+use std::fmt;
+
+use crate::items::*;
+
+/// The length up to which XOR clauses are expanded directly by `Instance::into_cnf`.
+const XOR_CUT_LEN: usize = 4;
+
+/// The encoding used to transform formulas into clauses.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    /// The Tseitin encoding with auxiliary variables equivalent to their subformulas.
+    Tseitin,
+
+    /// The polarity-aware Plaisted–Greenbaum encoding.
+    PlaistedGreenbaum,
+}
+
+/// The error returned when a transformation needs more auxiliary variables than there are
+/// representable variables above the variables of the instance.
+///
+/// This happens for instances declaring a number of variables close to `i64::MAX`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TransformError(());
+
+impl fmt::Display for TransformError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("auxiliary variables exceed the greatest representable variable")
+    }
+}
+
+impl error::Error for TransformError {}
+
+/// Represents the kind of gate that defines an auxiliary variable.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Gate {
+    /// The auxiliary variable is the logical and of its inputs.
+    And,
+
+    /// The auxiliary variable is the logical or of its inputs.
+    Or,
+
+    /// The auxiliary variable is the logical xor of its inputs.
+    Xor,
+
+    /// The auxiliary variable is true if all of its inputs are equal.
+    Eq,
+}
+
+/// Represents the definition of an auxiliary variable introduced by the transformation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Definition {
+    var: Var,
+    gate: Gate,
+    inputs: Box<[Lit]>,
+}
+
+impl Definition {
+    /// Returns the defined auxiliary variable.
+    pub fn var(&self) -> Var {
+        self.var
+    }
+
+    /// Returns the gate that defines the auxiliary variable.
+    pub fn gate(&self) -> Gate {
+        self.gate
+    }
+
+    /// Returns the literals the gate is applied to.
+    ///
+    /// These refer to variables of the original formula or to auxiliary variables
+    /// that were introduced before the defined variable.
+    pub fn inputs(&self) -> &[Lit] {
+        &self.inputs
+    }
+}
+
+/// Represents the auxiliary variables introduced by the transformation of a formula into clauses.
+///
+/// Auxiliary variables are numbered consecutively right above the original variables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuxVars {
+    num_original_vars: u64,
+    definitions: Box<[Definition]>,
+}
+
+impl AuxVars {
+    /// Returns the number of variables before the transformation.
+    pub fn num_original_vars(&self) -> u64 {
+        self.num_original_vars
+    }
+
+    /// Returns the number of variables after the transformation including auxiliary variables.
+    pub fn num_vars(&self) -> u64 {
+        self.num_original_vars + self.definitions.len() as u64
+    }
+
+    /// Returns the definitions of all auxiliary variables in ascending order of their variables.
+    pub fn definitions(&self) -> &[Definition] {
+        &self.definitions
+    }
+
+    /// Returns `true` if the given variable was introduced by the transformation.
+    pub fn is_aux(&self, var: Var) -> bool {
+        var.to_u64() > self.num_original_vars && var.to_u64() <= self.num_vars()
+    }
+
+    /// Returns the definition of the given auxiliary variable.
+    pub fn definition(&self, var: Var) -> Option<&Definition> {
+        if !self.is_aux(var) {
+            return None;
+        }
+        self.definitions
+            .get((var.to_u64() - self.num_original_vars - 1) as usize)
+    }
+
+    /// Projects a model of the transformed clauses back onto the original variables
+    /// by dropping all literals of auxiliary variables.
+    pub fn project(&self, model: &[Lit]) -> Vec<Lit> {
+        model
+            .iter()
+            .cloned()
+            .filter(|lit| !self.is_aux(lit.var()))
+            .collect()
+    }
+}
+
+/// The polarity in which a subformula occurs.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Polarity {
+    Pos,
+    Neg,
+    Both,
+}
+
+impl Polarity {
+    fn flip(self) -> Polarity {
+        match self {
+            Polarity::Pos => Polarity::Neg,
+            Polarity::Neg => Polarity::Pos,
+            Polarity::Both => Polarity::Both,
+        }
+    }
+
+    /// Returns `true` if the auxiliary variable has to imply its subformula.
+    fn pos(self) -> bool {
+        self != Polarity::Neg
+    }
+
+    /// Returns `true` if the subformula has to imply its auxiliary variable.
+    fn neg(self) -> bool {
+        self != Polarity::Pos
+    }
+}
+
+/// Returns the greatest variable of the given literals or `0` if there is none.
+fn max_var(lits: &[Lit]) -> u64 {
+    lits.iter().map(|lit| lit.var().to_u64()).max().unwrap_or(0)
+}
+
+/// Returns the greatest variable used within the given formula or `0` if there is none.
+fn max_formula_var(formula: &Formula) -> u64 {
+    use self::Formula::*;
+    match *formula {
+        Lit(lit) => lit.var().to_u64(),
+        Paren(ref inner) | Neg(ref inner) => max_formula_var(inner),
+        And(ref params) | Or(ref params) | Xor(ref params) | Eq(ref params) => {
+            params.iter().map(max_formula_var).max().unwrap_or(0)
+        }
+    }
+}
+
+fn negate(lit: Lit) -> Lit {
+    Lit::from_i64(-lit.to_i64())
+}
+
+struct Transformer {
+    encoding: Encoding,
+    num_original_vars: u64,
+    clauses: Vec<Clause>,
+    definitions: Vec<Definition>,
+}
+
+impl Transformer {
+    fn new(num_vars: u64, encoding: Encoding) -> Transformer {
+        Transformer {
+            encoding,
+            num_original_vars: num_vars,
+            clauses: Vec::new(),
+            definitions: Vec::new(),
+        }
+    }
+
+    fn add_clause(&mut self, lits: Vec<Lit>) {
+        self.clauses.push(Clause::from_vec(lits));
+    }
+
+    /// Introduces a fresh auxiliary variable with the given definition.
+    fn define(&mut self, gate: Gate, inputs: Vec<Lit>) -> Result<Lit, TransformError> {
+        let var = self
+            .num_original_vars
+            .checked_add(self.definitions.len() as u64 + 1)
+            .filter(|&var| var <= i64::MAX as u64)
+            .ok_or(TransformError(()))?;
+        self.definitions.push(Definition {
+            var: Var(var),
+            gate,
+            inputs: inputs.into_boxed_slice(),
+        });
+        Ok(Lit::from_i64(var as i64))
+    }
+
+    fn encode(&mut self, formula: &Formula, polarity: Polarity) -> Result<Lit, TransformError> {
+        use self::Formula::*;
+        let polarity = match self.encoding {
+            Encoding::Tseitin => Polarity::Both,
+            Encoding::PlaistedGreenbaum => polarity,
+        };
+        match *formula {
+            Lit(lit) => Ok(lit),
+            Paren(ref inner) => self.encode(inner, polarity),
+            Neg(ref inner) => Ok(negate(self.encode(inner, polarity.flip())?)),
+            And(ref params) => self.encode_and(params, polarity),
+            Or(ref params) => self.encode_or(params, polarity),
+            Xor(ref params) => self.encode_xor(params, polarity),
+            Eq(ref params) => self.encode_eq(params, polarity),
+        }
+    }
+
+    fn encode_params(
+        &mut self,
+        params: &[Formula],
+        polarity: Polarity,
+    ) -> Result<Vec<Lit>, TransformError> {
+        params
+            .iter()
+            .map(|param| self.encode(param, polarity))
+            .collect()
+    }
+
+    fn encode_and(
+        &mut self,
+        params: &[Formula],
+        polarity: Polarity,
+    ) -> Result<Lit, TransformError> {
+        let inputs = self.encode_params(params, polarity)?;
+        let x = self.define(Gate::And, inputs.clone())?;
+        if polarity.pos() {
+            for &lit in &inputs {
+                self.add_clause(vec![negate(x), lit]);
+            }
+        }
+        if polarity.neg() {
+            let mut clause = vec![x];
+            clause.extend(inputs.iter().cloned().map(negate));
+            self.add_clause(clause);
+        }
+        Ok(x)
+    }
+
+    fn encode_or(&mut self, params: &[Formula], polarity: Polarity) -> Result<Lit, TransformError> {
+        let inputs = self.encode_params(params, polarity)?;
+        let x = self.define(Gate::Or, inputs.clone())?;
+        if polarity.pos() {
+            let mut clause = vec![negate(x)];
+            clause.extend(inputs.iter().cloned());
+            self.add_clause(clause);
+        }
+        if polarity.neg() {
+            for &lit in &inputs {
+                self.add_clause(vec![x, negate(lit)]);
+            }
+        }
+        Ok(x)
+    }
+
+    /// Encodes n-ary xor formulas as a chain of binary xor gates.
+    fn encode_xor(
+        &mut self,
+        params: &[Formula],
+        polarity: Polarity,
+    ) -> Result<Lit, TransformError> {
+        let mut inputs = self.encode_params(params, Polarity::Both)?;
+        while inputs.len() > 2 {
+            let (a, b) = (inputs[0], inputs[1]);
+            let y = self.encode_binary_xor(a, b, Polarity::Both)?;
+            inputs.splice(0..2, Some(y));
+        }
+        match *inputs.as_slice() {
+            [a, b] => self.encode_binary_xor(a, b, polarity),
+            [a] => {
+                let x = self.define(Gate::Xor, vec![a])?;
+                if polarity.pos() {
+                    self.add_clause(vec![negate(x), a]);
+                }
+                if polarity.neg() {
+                    self.add_clause(vec![x, negate(a)]);
+                }
+                Ok(x)
+            }
+            _ => {
+                let x = self.define(Gate::Xor, vec![])?;
+                if polarity.pos() {
+                    self.add_clause(vec![negate(x)]);
+                }
+                Ok(x)
+            }
+        }
+    }
+
+    fn encode_binary_xor(
+        &mut self,
+        a: Lit,
+        b: Lit,
+        polarity: Polarity,
+    ) -> Result<Lit, TransformError> {
+        let x = self.define(Gate::Xor, vec![a, b])?;
+        if polarity.pos() {
+            self.add_clause(vec![negate(x), a, b]);
+            self.add_clause(vec![negate(x), negate(a), negate(b)]);
+        }
+        if polarity.neg() {
+            self.add_clause(vec![x, negate(a), b]);
+            self.add_clause(vec![x, a, negate(b)]);
+        }
+        Ok(x)
+    }
+
+    fn encode_eq(&mut self, params: &[Formula], polarity: Polarity) -> Result<Lit, TransformError> {
+        let inputs = self.encode_params(params, Polarity::Both)?;
+        let x = self.define(Gate::Eq, inputs.clone())?;
+        if polarity.pos() {
+            for pair in inputs.windows(2) {
+                let (a, b) = (pair[0], pair[1]);
+                self.add_clause(vec![negate(x), negate(a), b]);
+                self.add_clause(vec![negate(x), a, negate(b)]);
+            }
+        }
+        if polarity.neg() {
+            if inputs.len() <= 1 {
+                self.add_clause(vec![x]);
+            } else {
+                let mut all_true = vec![x];
+                all_true.extend(inputs.iter().cloned().map(negate));
+                self.add_clause(all_true);
+                let mut all_false = vec![x];
+                all_false.extend(inputs.iter().cloned());
+                self.add_clause(all_false);
+            }
+        }
+        Ok(x)
+    }
+
+    /// Adds the clauses of the given XOR clause, i.e. one clause for every combination
+    /// of an even number of negated literals.
+    fn add_parity_clauses(&mut self, lits: &[Lit]) {
+        for negated in 0..1u64 << lits.len() {
+            if negated.count_ones() % 2 == 0 {
+                let clause = lits
+                    .iter()
+                    .enumerate()
+                    .map(|(index, &lit)| match negated & 1 << index {
+                        0 => lit,
+                        _ => negate(lit),
+                    })
+                    .collect();
+                self.add_clause(clause);
+            }
+        }
+    }
+
+    /// Encodes the given XOR clause, cutting off the leading literals into auxiliary
+    /// variables defined as their xor until at most `cut_len` literals remain.
+    fn encode_xor_clause(&mut self, lits: &[Lit], cut_len: usize) -> Result<(), TransformError> {
+        let mut lits = lits.to_vec();
+        while lits.len() > cut_len {
+            let rest = lits.split_off(cut_len - 1);
+            let y = self.define(Gate::Xor, lits.clone())?;
+            lits.push(negate(y));
+            self.add_parity_clauses(&lits);
+            lits = Some(y).into_iter().chain(rest).collect();
+        }
+        self.add_parity_clauses(&lits);
+        Ok(())
+    }
+
+    fn finish(self) -> (Vec<Clause>, AuxVars) {
+        let aux = AuxVars {
+            num_original_vars: self.num_original_vars,
+            definitions: self.definitions.into_boxed_slice(),
+        };
+        (self.clauses, aux)
+    }
+}
+
+impl Formula {
+    /// Transforms this formula into equisatisfiable clauses with the given encoding.
+    ///
+    /// Auxiliary variables are introduced right above the given number of variables or
+    /// above the greatest variable used within this formula if that is greater, which is
+    /// reported by `AuxVars::num_original_vars`.
+    ///
+    /// Returns an error if an auxiliary variable would exceed `i64::MAX`.
+    pub fn to_cnf(
+        &self,
+        num_vars: u64,
+        encoding: Encoding,
+    ) -> Result<(Vec<Clause>, AuxVars), TransformError> {
+        let num_vars = num_vars.max(max_formula_var(self));
+        let mut transformer = Transformer::new(num_vars, encoding);
+        let root = transformer.encode(self, Polarity::Pos)?;
+        transformer.add_clause(vec![root]);
+        Ok(transformer.finish())
+    }
+}
+
+impl Instance {
+    /// Transforms this instance into an equisatisfiable `.cnf` instance with the given encoding.
+    ///
+    /// The formula of a `.sat` instance is transformed by `Formula::to_cnf` and XOR clauses
+    /// of `.cnf` instances are expanded by `Instance::expand_xors` cutting them into pieces
+    /// of four literals. Other `.cnf` and `.wcnf` instances are returned unchanged without
+    /// any auxiliary variables.
+    ///
+    /// The encoding only applies to `.sat` formulas. XOR clauses are always expanded the
+    /// same way since every piece of a cut XOR clause is required in both polarities;
+    /// use `Instance::expand_xors` directly to choose another cut length.
+    ///
+    /// Returns an error if an auxiliary variable would exceed `i64::MAX`.
+    pub fn into_cnf(self, encoding: Encoding) -> Result<(Instance, AuxVars), TransformError> {
+        match self {
+            Instance::Sat {
+                num_vars,
+                ref formula,
+                ..
+            } => {
+                let (clauses, aux) = formula.to_cnf(num_vars, encoding)?;
+                Ok((Instance::cnf(aux.num_vars(), clauses), aux))
+            }
+            Instance::Cnf { .. } => self.expand_xors(XOR_CUT_LEN),
+            Instance::Wcnf { num_vars, .. } => {
+                let aux = Transformer::new(num_vars, encoding).finish().1;
+                Ok((self, aux))
+            }
+        }
+    }
+
+    /// Expands the XOR clauses of a `.cnf` instance into plain clauses.
+    ///
+    /// XOR clauses with more than `cut_len` literals are cut into a chain of XOR clauses
+    /// of at most `cut_len` literals linked by auxiliary cutting variables which are
+    /// defined by `Gate::Xor`. Every XOR clause of `n` literals is expanded into
+    /// `2^(n-1)` clauses. Instances without XOR clauses are returned unchanged.
+    ///
+    /// Just like for `Formula::to_cnf` the cutting variables are introduced above the
+    /// declared number of variables or above the greatest variable used if that is greater.
+    /// Returns an error if a cutting variable would exceed `i64::MAX`.
+    ///
+    /// # Panics
+    ///
+    /// If `cut_len` is not within `3..=32`.
+    pub fn expand_xors(self, cut_len: usize) -> Result<(Instance, AuxVars), TransformError> {
+        assert!((3..=32).contains(&cut_len), "XOR cut length out of range");
+        let (num_vars, clauses, xors) = match self {
+            Instance::Cnf {
+                num_vars,
+                clauses,
+                xors,
+            } if !xors.is_empty() => (num_vars, clauses, xors),
+            Instance::Cnf { num_vars, .. }
+            | Instance::Sat { num_vars, .. }
+            | Instance::Wcnf { num_vars, .. } => {
+                let aux = Transformer::new(num_vars, Encoding::Tseitin).finish().1;
+                return Ok((self, aux));
+            }
+        };
+        let num_vars = clauses
+            .iter()
+            .map(|clause| max_var(clause.lits()))
+            .chain(xors.iter().map(|xor| max_var(xor.lits())))
+            .fold(num_vars, u64::max);
+        let mut transformer = Transformer::new(num_vars, Encoding::Tseitin);
+        transformer.clauses = clauses.into_vec();
+        for xor in xors.iter() {
+            transformer.encode_xor_clause(xor.lits(), cut_len)?;
+        }
+        let (clauses, aux) = transformer.finish();
+        Ok((Instance::cnf(aux.num_vars(), clauses), aux))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::parser::parse_dimacs;
+
+    fn lit(val: i64) -> Formula {
+        Formula::lit(Lit::from_i64(val))
+    }
+
+    fn is_true(lit: Lit, assignment: u64) -> bool {
+        let bit = assignment & (1 << (lit.var().to_u64() - 1)) != 0;
+        bit == (lit.sign() == Sign::Pos)
+    }
+
+    fn eval(formula: &Formula, assignment: u64) -> bool {
+        use self::Formula::*;
+        match *formula {
+            Lit(lit) => is_true(lit, assignment),
+            Paren(ref inner) => eval(inner, assignment),
+            Neg(ref inner) => !eval(inner, assignment),
+            And(ref params) => params.iter().all(|p| eval(p, assignment)),
+            Or(ref params) => params.iter().any(|p| eval(p, assignment)),
+            Xor(ref params) => params.iter().filter(|p| eval(p, assignment)).count() % 2 == 1,
+            Eq(ref params) => {
+                params.iter().all(|p| eval(p, assignment))
+                    || params.iter().all(|p| !eval(p, assignment))
+            }
+        }
+    }
+
+    fn satisfies(clauses: &[Clause], assignment: u64) -> bool {
+        clauses
+            .iter()
+            .all(|clause| clause.lits().iter().any(|&lit| is_true(lit, assignment)))
+    }
+
+    /// Checks by enumeration of all assignments that the clauses are satisfiable
+    /// under an assignment of the original variables exactly if the formula is.
+    fn assert_equisatisfiable(formula: &Formula, num_vars: u64, encoding: Encoding) {
+        let (clauses, aux) = formula
+            .to_cnf(num_vars, encoding)
+            .expect("representable aux vars");
+        let num_aux = aux.num_vars() - num_vars;
+        for original in 0..1u64 << num_vars {
+            let extensible =
+                (0..1u64 << num_aux).any(|ext| satisfies(&clauses, original | (ext << num_vars)));
+            assert_eq!(
+                extensible,
+                eval(formula, original),
+                "{:?} with {:?} under {:b}",
+                formula,
+                encoding,
+                original
+            );
+        }
+    }
+
+    #[test]
+    fn equisatisfiable() {
+        let formulas = vec![
+            lit(1),
+            Formula::and(vec![lit(1), lit(-2)]),
+            Formula::or(vec![]),
+            Formula::and(vec![]),
+            Formula::neg(Formula::or(vec![
+                lit(1),
+                Formula::and(vec![lit(2), lit(3)]),
+            ])),
+            Formula::xor(vec![lit(1), lit(2), lit(-3)]),
+            Formula::xor(vec![Formula::neg(lit(1))]),
+            Formula::xor(vec![]),
+            Formula::eq(vec![lit(1), Formula::or(vec![lit(2), lit(3)]), lit(-3)]),
+            Formula::eq(vec![lit(2)]),
+            Formula::neg(Formula::eq(vec![lit(1), lit(2)])),
+            Formula::paren(Formula::neg(Formula::xor(vec![
+                Formula::and(vec![lit(1), lit(2)]),
+                lit(3),
+            ]))),
+        ];
+        for formula in &formulas {
+            assert_equisatisfiable(formula, 3, Encoding::Tseitin);
+            assert_equisatisfiable(formula, 3, Encoding::PlaistedGreenbaum);
+        }
+    }
+
+    #[test]
+    fn plaisted_greenbaum_and() {
+        let formula = Formula::and(vec![lit(1), lit(-2)]);
+        let (clauses, aux) = formula
+            .to_cnf(2, Encoding::PlaistedGreenbaum)
+            .expect("representable aux vars");
+        let clause =
+            |lits: &[i64]| Clause::from_vec(lits.iter().cloned().map(Lit::from_i64).collect());
+        assert_eq!(
+            clauses,
+            vec![clause(&[-3, 1]), clause(&[-3, -2]), clause(&[3])]
+        );
+        let (clauses, _) = formula
+            .to_cnf(2, Encoding::Tseitin)
+            .expect("representable aux vars");
+        assert_eq!(
+            clauses,
+            vec![
+                clause(&[-3, 1]),
+                clause(&[-3, -2]),
+                clause(&[3, -1, 2]),
+                clause(&[3])
+            ]
+        );
+        assert_eq!(aux.num_vars(), 3);
+        assert!(aux.is_aux(Var(3)));
+        assert!(!aux.is_aux(Var(2)));
+        let definition = aux.definition(Var(3)).expect("defined aux var");
+        assert_eq!(definition.gate(), Gate::And);
+        assert_eq!(definition.inputs(), &[Lit::from_i64(1), Lit::from_i64(-2)]);
+        assert_eq!(
+            aux.project(&[Lit::from_i64(1), Lit::from_i64(-2), Lit::from_i64(3)]),
+            vec![Lit::from_i64(1), Lit::from_i64(-2)]
+        );
+    }
+
+    #[test]
+    fn expand_xors() {
+        let sample = r"
+			p cnf 6 3
+			1 2 0
+			x1 -2 3 -4 5 6 0
+			x-3 0";
+        let instance = parse_dimacs(sample).expect("valid .cnf");
+        let xor = |assignment: u64, lits: &[i64]| {
+            lits.iter()
+                .filter(|&&lit| is_true(Lit::from_i64(lit), assignment))
+                .count()
+                % 2
+                == 1
+        };
+        for &cut_len in &[3, 4, 6] {
+            let (cnf, aux) = instance
+                .clone()
+                .expand_xors(cut_len)
+                .expect("representable aux vars");
+            let clauses = match cnf {
+                Instance::Cnf {
+                    num_vars,
+                    clauses,
+                    xors,
+                } => {
+                    assert_eq!(num_vars, aux.num_vars());
+                    assert!(xors.is_empty());
+                    clauses
+                }
+                _ => panic!("expected a .cnf instance"),
+            };
+            assert!(aux
+                .definitions()
+                .iter()
+                .all(|definition| definition.gate() == Gate::Xor));
+            let num_aux = aux.num_vars() - 6;
+            for original in 0..1u64 << 6 {
+                let extensible =
+                    (0..1u64 << num_aux).any(|ext| satisfies(&clauses, original | (ext << 6)));
+                let expected = (is_true(Lit::from_i64(1), original)
+                    || is_true(Lit::from_i64(2), original))
+                    && xor(original, &[1, -2, 3, -4, 5, 6])
+                    && xor(original, &[-3]);
+                assert_eq!(extensible, expected, "cut {} under {:b}", cut_len, original);
+            }
+        }
+        let (cnf, aux) = instance
+            .clone()
+            .expand_xors(6)
+            .expect("representable aux vars");
+        assert_eq!(aux.num_vars(), 6);
+        assert_eq!(
+            cnf.clone()
+                .into_cnf(Encoding::Tseitin)
+                .expect("representable aux vars")
+                .0,
+            cnf
+        );
+        let (cnf, aux) = instance
+            .into_cnf(Encoding::Tseitin)
+            .expect("representable aux vars");
+        assert_eq!(aux.num_vars(), 7);
+        assert_eq!(
+            cnf.expand_xors(3)
+                .expect("representable aux vars")
+                .1
+                .num_vars(),
+            7
+        );
+    }
+
+    #[test]
+    fn formula_exceeding_header() {
+        let instance = parse_dimacs("p sat 1 (+(1 2))").expect("valid unsafe .sat");
+        let (cnf, aux) = instance
+            .into_cnf(Encoding::Tseitin)
+            .expect("representable aux vars");
+        assert_eq!(aux.num_original_vars(), 2);
+        assert_eq!(aux.num_vars(), 3);
+        assert!(!aux.is_aux(Var(2)));
+        assert!(aux.is_aux(Var(3)));
+        match cnf {
+            Instance::Cnf { num_vars, .. } => assert_eq!(num_vars, 3),
+            _ => panic!("expected a .cnf instance"),
+        }
+    }
+
+    #[test]
+    fn aux_vars_exceeding_i64() {
+        let instance = parse_dimacs("p sat 9223372036854775806 (+(1 2))").expect("valid .sat");
+        let (_, aux) = instance
+            .into_cnf(Encoding::Tseitin)
+            .expect("representable aux vars");
+        assert_eq!(aux.num_vars(), i64::MAX as u64);
+        for header in &["9223372036854775807", "18446744073709551615"] {
+            let sample = format!("p sat {} (+(1 2))", header);
+            let instance = parse_dimacs(&sample).expect("valid .sat");
+            assert_eq!(
+                instance.into_cnf(Encoding::Tseitin),
+                Err(TransformError(()))
+            );
+        }
+    }
+
+    #[test]
+    fn cutting_vars_exceeding_i64() {
+        let sample = "p cnf 18446744073709551615 1\nx1 2 3 4 5 6 0\n";
+        let instance = parse_dimacs(sample).expect("valid .cnf");
+        assert_eq!(
+            instance.clone().into_cnf(Encoding::PlaistedGreenbaum),
+            Err(TransformError(()))
+        );
+        assert_eq!(
+            instance.expand_xors(6).map(|(_, aux)| aux.num_vars()),
+            Ok(u64::MAX)
+        );
+    }
+
+    #[test]
+    fn xors_exceeding_header() {
+        let instance = parse_dimacs("p cnf 2 2\n-6 0\nx1 2 3 4 5 0").expect("valid unsafe .cnf");
+        let (_, aux) = instance.expand_xors(3).expect("representable aux vars");
+        assert_eq!(aux.num_original_vars(), 6);
+        assert!(aux.definitions().iter().all(|def| def.var().to_u64() > 6));
+        assert_eq!(aux.num_vars(), 8);
+    }
+
+    #[test]
+    fn instance_into_cnf() {
+        let sample = r"
+			p satex 3
+			(*(+(1 -2) xor(2 3) =(1 3)))";
+        let instance = parse_dimacs(sample).expect("valid .sat");
+        let (cnf, aux) = instance
+            .into_cnf(Encoding::PlaistedGreenbaum)
+            .expect("representable aux vars");
+        assert_eq!(aux.num_original_vars(), 3);
+        match cnf {
+            Instance::Cnf {
+                num_vars, clauses, ..
+            } => {
+                assert_eq!(num_vars, aux.num_vars());
+                assert!(clauses
+                    .iter()
+                    .flat_map(|clause| clause.lits())
+                    .all(|lit| lit.var().to_u64() <= num_vars));
+            }
+            _ => panic!("expected a .cnf instance"),
+        }
+        let cnf = Instance::cnf(2, vec![Clause::from_vec(vec![Lit::from_i64(1)])]);
+        let (same, aux) = cnf
+            .clone()
+            .into_cnf(Encoding::Tseitin)
+            .expect("representable aux vars");
+        assert_eq!(same, cnf);
+        assert_eq!(aux.num_vars(), 2);
+        assert!(aux.definitions().is_empty());
+    }
+}
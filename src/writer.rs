@@ -0,0 +1,632 @@
+//! The writer facility for writing `.cnf`, `.sat`, `.qdimacs`, `.icnf`, graph and network files
+//! as well as SAT solver outputs as specified in the
+//! [DIMACS format specification](http://www.domagoj-babic.com/uploads/ResearchProjects/Spear/dimacs-cnf.pdf).
+//!
+//! Writing is the inverse operation of parsing: every `Instance` written by this
+//! module is parsed back into an equal `Instance` by `parse_dimacs`.
+//!
+//! `.wcnf` instances are written with a `p wcnf` problem line that declares their number
+//! of variables. Its top weight exceeds the sum of all soft weights, so writing fails with
+//! an `io::ErrorKind::InvalidInput` error if that sum does not fit into a `u64`.
+
+use std::io::{self, Write};
+
+use crate::items::*;
+
+/// The number of literals written per `v` line of a solver output.
+const MAX_VALUES_PER_LINE: usize = 10;
+
+struct Writer<W>
+where
+    W: Write,
+{
+    out: W,
+}
+
+impl<W> Writer<W>
+where
+    W: Write,
+{
+    fn from(out: W) -> Writer<W> {
+        Writer { out }
+    }
+
+    fn write_comments(&mut self, comments: &[&str]) -> io::Result<()> {
+        for line in comments.iter().flat_map(|comment| comment.split('\n')) {
+            if line.is_empty() {
+                writeln!(self.out, "c")?;
+            } else {
+                writeln!(self.out, "c {}", line)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_lit(&mut self, lit: Lit) -> io::Result<()> {
+        write!(self.out, "{}", lit.to_i64())
+    }
+
+    fn write_clause(&mut self, clause: &Clause) -> io::Result<()> {
+        for &lit in clause.lits() {
+            self.write_lit(lit)?;
+            write!(self.out, " ")?;
+        }
+        writeln!(self.out, "0")
+    }
+
+    fn write_xor_clause(&mut self, xor: &XorClause) -> io::Result<()> {
+        write!(self.out, "x")?;
+        for &lit in xor.lits() {
+            self.write_lit(lit)?;
+            write!(self.out, " ")?;
+        }
+        writeln!(self.out, "0")
+    }
+
+    fn write_cnf(
+        &mut self,
+        num_vars: u64,
+        clauses: &[Clause],
+        xors: &[XorClause],
+    ) -> io::Result<()> {
+        writeln!(
+            self.out,
+            "p cnf {} {}",
+            num_vars,
+            clauses.len() + xors.len()
+        )?;
+        for clause in clauses {
+            self.write_clause(clause)?;
+        }
+        for xor in xors {
+            self.write_xor_clause(xor)?;
+        }
+        Ok(())
+    }
+
+    fn write_wcnf(&mut self, num_vars: u64, clauses: &[WeightedClause]) -> io::Result<()> {
+        let top = clauses
+            .iter()
+            .try_fold(1u64, |top, clause| match clause.weight() {
+                Weight::Hard => Some(top),
+                Weight::Soft(weight) => top.checked_add(weight),
+            })
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "the sum of soft weights does not fit into a `p wcnf` problem line",
+                )
+            })?;
+        writeln!(self.out, "p wcnf {} {} {}", num_vars, clauses.len(), top)?;
+        for clause in clauses {
+            match clause.weight() {
+                Weight::Hard => write!(self.out, "{} ", top)?,
+                Weight::Soft(weight) => write!(self.out, "{} ", weight)?,
+            }
+            self.write_clause(clause.clause())?;
+        }
+        Ok(())
+    }
+
+    fn write_sat_extensions(&mut self, extensions: Extensions) -> io::Result<()> {
+        let xor = extensions.contains(Extensions::XOR);
+        let eq = extensions.contains(Extensions::EQ);
+        let kind = match (eq, xor) {
+            (false, false) => "sat",
+            (true, false) => "sate",
+            (false, true) => "satx",
+            (true, true) => "satex",
+        };
+        write!(self.out, "{}", kind)
+    }
+
+    fn write_formula_params(&mut self, params: &[Formula]) -> io::Result<()> {
+        write!(self.out, "(")?;
+        for (n, param) in params.iter().enumerate() {
+            if n != 0 {
+                write!(self.out, " ")?;
+            }
+            self.write_formula(param)?;
+        }
+        write!(self.out, ")")
+    }
+
+    fn write_formula(&mut self, formula: &Formula) -> io::Result<()> {
+        use self::Formula::*;
+        match *formula {
+            Lit(lit) => self.write_lit(lit),
+            Paren(ref inner) => {
+                write!(self.out, "(")?;
+                self.write_formula(inner)?;
+                write!(self.out, ")")
+            }
+            Neg(ref inner) => {
+                write!(self.out, "-(")?;
+                self.write_formula(inner)?;
+                write!(self.out, ")")
+            }
+            And(ref params) => {
+                write!(self.out, "*")?;
+                self.write_formula_params(params)
+            }
+            Or(ref params) => {
+                write!(self.out, "+")?;
+                self.write_formula_params(params)
+            }
+            Xor(ref params) => {
+                write!(self.out, "xor")?;
+                self.write_formula_params(params)
+            }
+            Eq(ref params) => {
+                write!(self.out, "=")?;
+                self.write_formula_params(params)
+            }
+        }
+    }
+
+    fn write_sat(
+        &mut self,
+        num_vars: u64,
+        extensions: Extensions,
+        formula: &Formula,
+    ) -> io::Result<()> {
+        write!(self.out, "p ")?;
+        self.write_sat_extensions(extensions)?;
+        writeln!(self.out, " {}", num_vars)?;
+        // The parser requires the top-level formula of a `.sat` file to be
+        // parenthesized and always yields a `Formula::Paren` for it.
+        match *formula {
+            Formula::Paren(_) => self.write_formula(formula)?,
+            _ => {
+                write!(self.out, "(")?;
+                self.write_formula(formula)?;
+                write!(self.out, ")")?;
+            }
+        }
+        writeln!(self.out)
+    }
+
+    fn write_instance(&mut self, instance: &Instance) -> io::Result<()> {
+        match *instance {
+            Instance::Cnf {
+                num_vars,
+                ref clauses,
+                ref xors,
+            } => self.write_cnf(num_vars, clauses, xors),
+            Instance::Sat {
+                num_vars,
+                extensions,
+                ref formula,
+            } => self.write_sat(num_vars, extensions, formula),
+            Instance::Wcnf {
+                num_vars,
+                ref clauses,
+            } => self.write_wcnf(num_vars, clauses),
+        }
+    }
+
+    fn write_quantifier_block(&mut self, block: &QuantifierBlock) -> io::Result<()> {
+        match block.quantifier() {
+            Quantifier::Exists => write!(self.out, "e ")?,
+            Quantifier::Forall => write!(self.out, "a ")?,
+        }
+        for var in block.vars() {
+            write!(self.out, "{} ", var.to_u64())?;
+        }
+        writeln!(self.out, "0")
+    }
+
+    fn write_qdimacs(&mut self, instance: &QdimacsInstance) -> io::Result<()> {
+        writeln!(
+            self.out,
+            "p cnf {} {}",
+            instance.num_vars(),
+            instance.clauses().len()
+        )?;
+        for block in instance.prefix() {
+            self.write_quantifier_block(block)?;
+        }
+        for clause in instance.clauses() {
+            self.write_clause(clause)?;
+        }
+        self.out.flush()
+    }
+
+    fn write_icnf(&mut self, instance: &IncrementalInstance) -> io::Result<()> {
+        writeln!(self.out, "p inccnf")?;
+        for step in instance.steps() {
+            match step {
+                IncrementalStep::Add(clause) => self.write_clause(clause)?,
+                IncrementalStep::Solve(assumptions) => {
+                    write!(self.out, "a ")?;
+                    for &lit in assumptions.iter() {
+                        self.write_lit(lit)?;
+                        write!(self.out, " ")?;
+                    }
+                    writeln!(self.out, "0")?;
+                }
+            }
+        }
+        self.out.flush()
+    }
+
+    fn write_graph(&mut self, graph: &Graph) -> io::Result<()> {
+        let kind = match graph.kind() {
+            GraphKind::Edge => "edge",
+            GraphKind::Col => "col",
+        };
+        writeln!(
+            self.out,
+            "p {} {} {}",
+            kind,
+            graph.num_vertices(),
+            graph.edges().len()
+        )?;
+        for weight in graph.weights() {
+            writeln!(self.out, "n {} {}", weight.vertex(), weight.weight())?;
+        }
+        for edge in graph.edges() {
+            let (u, v) = edge.vertices();
+            writeln!(self.out, "e {} {}", u, v)?;
+        }
+        self.out.flush()
+    }
+
+    fn write_network(&mut self, network: &Network) -> io::Result<()> {
+        match *network {
+            Network::ShortestPath {
+                num_nodes,
+                ref arcs,
+            } => {
+                writeln!(self.out, "p sp {} {}", num_nodes, arcs.len())?;
+                for arc in arcs.iter() {
+                    writeln!(self.out, "a {} {} {}", arc.from(), arc.to(), arc.weight())?;
+                }
+            }
+            Network::MaxFlow {
+                num_nodes,
+                source,
+                sink,
+                ref arcs,
+            } => {
+                writeln!(self.out, "p max {} {}", num_nodes, arcs.len())?;
+                writeln!(self.out, "n {} s", source)?;
+                writeln!(self.out, "n {} t", sink)?;
+                for arc in arcs.iter() {
+                    writeln!(self.out, "a {} {} {}", arc.from(), arc.to(), arc.capacity())?;
+                }
+            }
+            Network::MinCostFlow {
+                num_nodes,
+                ref supplies,
+                ref arcs,
+            } => {
+                writeln!(self.out, "p min {} {}", num_nodes, arcs.len())?;
+                for supply in supplies.iter() {
+                    writeln!(self.out, "n {} {}", supply.node(), supply.supply())?;
+                }
+                for arc in arcs.iter() {
+                    writeln!(
+                        self.out,
+                        "a {} {} {} {} {}",
+                        arc.from(),
+                        arc.to(),
+                        arc.lower(),
+                        arc.capacity(),
+                        arc.cost()
+                    )?;
+                }
+            }
+        }
+        self.out.flush()
+    }
+
+    fn write_solver_output(&mut self, output: &SolverOutput) -> io::Result<()> {
+        let status = match output.status() {
+            Status::Satisfiable => "SATISFIABLE",
+            Status::Unsatisfiable => "UNSATISFIABLE",
+            Status::Unknown => "UNKNOWN",
+        };
+        if output.status() != Status::Satisfiable && !output.model().is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "only satisfiable solver outputs can have a model",
+            ));
+        }
+        writeln!(self.out, "s {}", status)?;
+        if output.status() == Status::Satisfiable {
+            for lits in output.model().chunks(MAX_VALUES_PER_LINE) {
+                write!(self.out, "v")?;
+                for &lit in lits {
+                    write!(self.out, " ")?;
+                    self.write_lit(lit)?;
+                }
+                writeln!(self.out)?;
+            }
+            writeln!(self.out, "v 0")?;
+        }
+        self.out.flush()
+    }
+
+    fn write_dimacs(&mut self, instance: &Instance, comments: &[&str]) -> io::Result<()> {
+        self.write_comments(comments)?;
+        self.write_instance(instance)?;
+        self.out.flush()
+    }
+}
+
+/// Writes the given SAT instance as `.cnf`, `.sat` or `.wcnf` file as specified in
+/// [DIMACS format specification](http://www.domagoj-babic.com/uploads/ResearchProjects/Spear/dimacs-cnf.pdf).
+///
+/// The output is unbuffered, so wrapping `out` in a `BufWriter` is recommended for large instances.
+pub fn write_dimacs<W: Write>(instance: &Instance, out: W) -> io::Result<()> {
+    write_dimacs_with_comments(instance, &[], out)
+}
+
+/// Writes the given SAT instance just like `write_dimacs` but precedes the problem line
+/// with the given comments, each line of a comment being written as its own `c` line.
+pub fn write_dimacs_with_comments<W: Write>(
+    instance: &Instance,
+    comments: &[&str],
+    out: W,
+) -> io::Result<()> {
+    Writer::from(out).write_dimacs(instance, comments)
+}
+
+/// Writes the given QBF instance as `.qdimacs` file as specified in the
+/// [QDIMACS standard](http://www.qbflib.org/qdimacs.html).
+pub fn write_qdimacs<W: Write>(instance: &QdimacsInstance, out: W) -> io::Result<()> {
+    Writer::from(out).write_qdimacs(instance)
+}
+
+/// Writes the given incremental SAT problem as `.icnf` file with a `p inccnf` problem line.
+pub fn write_icnf<W: Write>(instance: &IncrementalInstance, out: W) -> io::Result<()> {
+    Writer::from(out).write_icnf(instance)
+}
+
+/// Writes the given graph as DIMACS `p edge` or `p col` file with all vertex weights
+/// preceding the edges.
+pub fn write_graph<W: Write>(graph: &Graph, out: W) -> io::Result<()> {
+    Writer::from(out).write_graph(graph)
+}
+
+/// Writes the given network as DIMACS `p sp`, `p max` or `p min` file.
+pub fn write_network<W: Write>(network: &Network, out: W) -> io::Result<()> {
+    Writer::from(out).write_network(network)
+}
+
+/// Writes the given solver output in the format of the SAT competitions.
+///
+/// The model is written in `v` lines of at most ten literals followed by a final `v 0` line.
+/// Only satisfiable outputs can have a model, otherwise an `io::ErrorKind::InvalidInput`
+/// error is returned.
+pub fn write_solver_output<W: Write>(output: &SolverOutput, out: W) -> io::Result<()> {
+    Writer::from(out).write_solver_output(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::parser::{
+        parse_dimacs, parse_graph, parse_icnf, parse_network, parse_qdimacs, parse_solver_output,
+    };
+
+    fn to_string(instance: &Instance, comments: &[&str]) -> String {
+        let mut buf = Vec::new();
+        write_dimacs_with_comments(instance, comments, &mut buf).expect("valid write");
+        String::from_utf8(buf).expect("valid utf8")
+    }
+
+    #[test]
+    fn simple_cnf() {
+        let instance = Instance::cnf(
+            42,
+            vec![
+                Clause::from_vec(vec![Lit::from_i64(1), Lit::from_i64(2)]),
+                Clause::from_vec(vec![Lit::from_i64(-3), Lit::from_i64(4)]),
+                Clause::from_vec(vec![]),
+            ],
+        );
+        let written = to_string(&instance, &["Sample DIMACS .cnf file", "", "two\nlines"]);
+        assert_eq!(
+            written,
+            "c Sample DIMACS .cnf file\nc\nc two\nc lines\np cnf 42 3\n1 2 0\n-3 4 0\n0\n"
+        );
+        assert_eq!(parse_dimacs(&written), Ok(instance));
+    }
+
+    #[test]
+    fn simple_sat() {
+        let sample = r"
+			c Sample DIMACS .sat file
+			p satex 42
+			(*(+(1 3 -4)
+			+(4)
+			xor(2 -(=(3 (-5))))))";
+        let parsed = parse_dimacs(sample).expect("valid .sat");
+        let written = to_string(&parsed, &[]);
+        assert_eq!(
+            written,
+            "p satex 42\n(*(+(1 3 -4) +(4) xor(2 -(=(3 (-5))))))\n"
+        );
+        assert_eq!(parse_dimacs(&written), Ok(parsed));
+    }
+
+    #[test]
+    fn sat_extensions() {
+        for &(extensions, kind) in &[
+            (Extensions::NONE, "sat"),
+            (Extensions::EQ, "sate"),
+            (Extensions::XOR, "satx"),
+            (Extensions::EQ | Extensions::XOR, "satex"),
+        ] {
+            let instance = Instance::sat(
+                1,
+                extensions,
+                Formula::paren(Formula::lit(Lit::from_i64(1))),
+            );
+            let written = to_string(&instance, &[]);
+            assert_eq!(written, format!("p {} 1\n(1)\n", kind));
+            assert_eq!(parse_dimacs(&written), Ok(instance));
+        }
+    }
+
+    #[test]
+    fn unparenthesized_sat() {
+        let instance = Instance::sat(
+            2,
+            Extensions::NONE,
+            Formula::and(vec![
+                Formula::lit(Lit::from_i64(1)),
+                Formula::lit(Lit::from_i64(-2)),
+            ]),
+        );
+        let written = to_string(&instance, &[]);
+        assert_eq!(written, "p sat 2\n(*(1 -2))\n");
+        assert_eq!(
+            parse_dimacs(&written),
+            Ok(Instance::sat(
+                2,
+                Extensions::NONE,
+                Formula::paren(Formula::and(vec![
+                    Formula::lit(Lit::from_i64(1)),
+                    Formula::lit(Lit::from_i64(-2)),
+                ])),
+            ))
+        );
+    }
+
+    #[test]
+    fn simple_wcnf() {
+        let sample = r"
+			c Sample DIMACS .wcnf file
+			p wcnf 4 3 16
+			16 1 -2 4 0
+			8 -2 -4 0
+			4 -3 2 0";
+        let parsed = parse_dimacs(sample).expect("valid .wcnf");
+        let written = to_string(&parsed, &[]);
+        assert_eq!(written, "p wcnf 4 3 13\n13 1 -2 4 0\n8 -2 -4 0\n4 -3 2 0\n");
+        assert_eq!(parse_dimacs(&written), Ok(parsed));
+
+        let empty = Instance::wcnf(0, vec![]);
+        let written = to_string(&empty, &[]);
+        assert_eq!(written, "p wcnf 0 0 1\n");
+        assert_eq!(parse_dimacs(&written), Ok(empty));
+
+        let huge = Instance::wcnf(
+            1,
+            vec![WeightedClause::new(
+                Weight::Soft(u64::MAX),
+                Clause::from_vec(vec![Lit::from_i64(1)]),
+            )],
+        );
+        let err = write_dimacs(&huge, Vec::new()).expect_err("unrepresentable weights");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn simple_qdimacs() {
+        let sample = r"
+			c Sample QDIMACS file
+			p cnf 4 2
+			a 1 2 0
+			e 3 4 0
+			-1 2 3 0
+			1 -4 0";
+        let parsed = parse_qdimacs(sample).expect("valid .qdimacs");
+        let mut buf = Vec::new();
+        write_qdimacs(&parsed, &mut buf).expect("valid write");
+        let written = String::from_utf8(buf).expect("valid utf8");
+        assert_eq!(written, "p cnf 4 2\na 1 2 0\ne 3 4 0\n-1 2 3 0\n1 -4 0\n");
+        assert_eq!(parse_qdimacs(&written), Ok(parsed));
+    }
+
+    #[test]
+    fn cnf_with_xors() {
+        let sample = r"
+			p cnf 3 3
+			1 -2 0
+			x1 -2 3 0
+			x0";
+        let parsed = parse_dimacs(sample).expect("valid .cnf");
+        let written = to_string(&parsed, &[]);
+        assert_eq!(written, "p cnf 3 3\n1 -2 0\nx1 -2 3 0\nx0\n");
+        assert_eq!(parse_dimacs(&written), Ok(parsed));
+    }
+
+    #[test]
+    fn simple_icnf() {
+        let sample = r"
+			p inccnf
+			1 -2 0
+			a 2 0
+			-1 3 0
+			a -3 2 0";
+        let parsed = parse_icnf(sample).expect("valid .icnf");
+        let mut buf = Vec::new();
+        write_icnf(&parsed, &mut buf).expect("valid write");
+        let written = String::from_utf8(buf).expect("valid utf8");
+        assert_eq!(written, "p inccnf\n1 -2 0\na 2 0\n-1 3 0\na -3 2 0\n");
+        assert_eq!(parse_icnf(&written), Ok(parsed));
+    }
+
+    #[test]
+    fn simple_graph() {
+        let sample = r"
+			c Sample DIMACS graph file
+			p edge 3 2
+			e 1 2
+			n 3 7
+			e 2 3";
+        let parsed = parse_graph(sample).expect("valid graph");
+        let mut buf = Vec::new();
+        write_graph(&parsed, &mut buf).expect("valid write");
+        let written = String::from_utf8(buf).expect("valid utf8");
+        assert_eq!(written, "p edge 3 2\nn 3 7\ne 1 2\ne 2 3\n");
+        assert_eq!(parse_graph(&written), Ok(parsed));
+    }
+
+    #[test]
+    fn networks() {
+        for &(sample, kind) in &[
+            ("p sp 3 2\na 1 2 7\na 2 3 0\n", "sp"),
+            ("p max 3 2\nn 1 s\nn 3 t\na 1 2 5\na 2 3 4\n", "max"),
+            (
+                "p min 3 2\nn 1 4\nn 3 -4\na 1 2 0 4 -2\na 2 3 1 5 3\n",
+                "min",
+            ),
+        ] {
+            let parsed = parse_network(sample).expect(kind);
+            let mut buf = Vec::new();
+            write_network(&parsed, &mut buf).expect("valid write");
+            assert_eq!(String::from_utf8(buf).expect("valid utf8"), sample);
+        }
+    }
+
+    #[test]
+    fn solver_output() {
+        let model: Vec<Lit> = (1..=12)
+            .map(|val| Lit::from_i64(if val % 3 == 0 { -val } else { val }))
+            .collect();
+        let output = SolverOutput::new(Status::Satisfiable, model);
+        let mut buf = Vec::new();
+        write_solver_output(&output, &mut buf).expect("valid write");
+        let written = String::from_utf8(buf).expect("valid utf8");
+        assert_eq!(
+            written,
+            "s SATISFIABLE\nv 1 2 -3 4 5 -6 7 8 -9 10\nv 11 -12\nv 0\n"
+        );
+        assert_eq!(parse_solver_output(&written), Ok(output));
+
+        let mut buf = Vec::new();
+        write_solver_output(&SolverOutput::new(Status::Unknown, vec![]), &mut buf)
+            .expect("valid write");
+        assert_eq!(String::from_utf8(buf).expect("valid utf8"), "s UNKNOWN\n");
+
+        let unsat = SolverOutput::new(Status::Unsatisfiable, vec![Lit::from_i64(1)]);
+        let err = write_solver_output(&unsat, Vec::new()).expect_err("model of unsat output");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}